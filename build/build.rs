@@ -17,14 +17,80 @@ use std::process::{self, Command};
 
 fn main() {
     println!("cargo:rerun-if-changed=build/build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTVERSION_OVERRIDE");
+    println!("cargo:rerun-if-env-changed=RUSTVERSION_DOCS");
+    println!("cargo:rerun-if-env-changed=RUSTDOCFLAGS");
 
+    let version = detect_version();
+
+    if (version.major, version.minor) < (1, 38) {
+        // Prior to 1.38, a #[proc_macro] is not allowed to be named `cfg`.
+        println!("cargo:rustc-cfg=cfg_macro_not_allowed");
+    }
+
+    if (version.major, version.minor) >= (1, 80) {
+        println!("cargo:rustc-check-cfg=cfg(cfg_macro_not_allowed)");
+        println!("cargo:rustc-check-cfg=cfg(docs_mode)");
+        println!("cargo:rustc-check-cfg=cfg(host_os, values(\"windows\"))");
+    }
+
+    // Documentation mode is requested either directly, or by `--cfg
+    // rustversion_docs` in RUSTDOCFLAGS. Build scripts see RUSTDOCFLAGS only
+    // as an environment variable, not the flags passed by `cargo rustdoc --`
+    // or configured as build.rustdocflags.
+    if is_set("RUSTVERSION_DOCS") || rustdocflags_have_cfg("rustversion_docs") {
+        println!("cargo:rustc-cfg=docs_mode");
+    }
+
+    // The override only affects what the macros evaluate against. Which
+    // cfgs are emitted above still depends on the real compiler.
+    let version = match env::var_os("RUSTVERSION_OVERRIDE") {
+        Some(string) => parse_override(&string),
+        None => version,
+    };
+
+    let version = format!("{:#}\n", Render(&version));
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR not set");
+    let out_file = Path::new(&out_dir).join("version.expr");
+    fs::write(out_file, version).expect("failed to write version.expr");
+
+    let host = env::var_os("HOST").expect("HOST not set");
+    if let Some("windows") = host.to_str().unwrap().split('-').nth(2) {
+        println!("cargo:rustc-cfg=host_os=\"windows\"");
+    }
+}
+
+fn is_set(var: &str) -> bool {
+    env::var_os(var).map_or(false, |value| !value.is_empty())
+}
+
+fn rustdocflags_have_cfg(name: &str) -> bool {
+    let rustdocflags = env::var("RUSTDOCFLAGS").unwrap_or_default();
+    let mut flags = rustdocflags.split_whitespace();
+    while let Some(flag) = flags.next() {
+        let cfg = if flag == "--cfg" {
+            flags.next()
+        } else if flag.starts_with("--cfg=") {
+            Some(&flag["--cfg=".len()..])
+        } else {
+            continue;
+        };
+        if cfg == Some(name) {
+            return true;
+        }
+    }
+    false
+}
+
+fn detect_version() -> rustc::Version {
     let rustc = env::var_os("RUSTC").unwrap_or_else(|| OsString::from("rustc"));
     let rustc_wrapper = env::var_os("RUSTC_WRAPPER").filter(|wrapper| !wrapper.is_empty());
     let wrapped_rustc = rustc_wrapper.iter().chain(iter::once(&rustc));
 
     let mut is_clippy_driver = false;
     let mut is_mirai = false;
-    let version = loop {
+    let mut is_verbose = true;
+    loop {
         let mut command;
         if is_mirai {
             command = Command::new(&rustc);
@@ -36,13 +102,17 @@ fn main() {
         if is_clippy_driver {
             command.arg("--rustc");
         }
-        command.arg("--version");
+        // Prefer `rustc -vV` for the commit hash, commit date, host and LLVM
+        // version, but fall back to `rustc --version` for wrappers that only
+        // understand the latter.
+        let flag = if is_verbose { "-vV" } else { "--version" };
+        command.arg(flag);
 
         let output = match command.output() {
             Ok(output) => output,
             Err(e) => {
                 let rustc = rustc.to_string_lossy();
-                eprintln!("Error: failed to run `{} --version`: {}", rustc, e);
+                eprintln!("Error: failed to run `{} {}`: {}", rustc, flag, e);
                 process::exit(1);
             }
         };
@@ -52,54 +122,64 @@ fn main() {
             Err(e) => {
                 let rustc = rustc.to_string_lossy();
                 eprintln!(
-                    "Error: failed to parse output of `{} --version`: {}",
-                    rustc, e,
+                    "Error: failed to parse output of `{} {}`: {}",
+                    rustc, flag, e,
                 );
                 process::exit(1);
             }
         };
 
-        break match rustc::parse(&string) {
-            rustc::ParseResult::Success(version) => version,
-            rustc::ParseResult::OopsClippy if !is_clippy_driver => {
-                is_clippy_driver = true;
-                continue;
-            }
+        match rustc::parse(&string) {
+            rustc::ParseResult::Success(version) => return version,
+            rustc::ParseResult::OopsClippy if !is_clippy_driver => is_clippy_driver = true,
             rustc::ParseResult::OopsMirai if !is_mirai && rustc_wrapper.is_some() => {
                 is_mirai = true;
-                continue;
             }
+            rustc::ParseResult::Unrecognized if is_verbose => is_verbose = false,
             rustc::ParseResult::Unrecognized
             | rustc::ParseResult::OopsClippy
             | rustc::ParseResult::OopsMirai => {
                 eprintln!(
-                    "Error: unexpected output from `rustc --version`: {:?}\n\n\
+                    "Error: unexpected output from `rustc {}`: {:?}\n\n\
                     Please file an issue in https://github.com/dtolnay/rustversion",
-                    string
+                    flag, string,
                 );
                 process::exit(1);
             }
-        };
-    };
-
-    if version.minor < 38 {
-        // Prior to 1.38, a #[proc_macro] is not allowed to be named `cfg`.
-        println!("cargo:rustc-cfg=cfg_macro_not_allowed");
+        }
     }
+}
 
-    if version.minor >= 80 {
-        println!("cargo:rustc-check-cfg=cfg(cfg_macro_not_allowed)");
-        println!("cargo:rustc-check-cfg=cfg(host_os, values(\"windows\"))");
-    }
+fn parse_override(string: &OsString) -> rustc::Version {
+    let string = match string.to_str() {
+        Some(string) => string.trim(),
+        None => {
+            eprintln!("Error: RUSTVERSION_OVERRIDE is not valid UTF-8");
+            process::exit(1);
+        }
+    };
 
-    let version = format!("{:#}\n", Render(&version));
-    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR not set");
-    let out_file = Path::new(&out_dir).join("version.expr");
-    fs::write(out_file, version).expect("failed to write version.expr");
+    // Accept either the bare version, like `1.70.0`, or the full output of
+    // `rustc --version`.
+    let line = if string.starts_with("rustc ") {
+        string.to_owned()
+    } else {
+        format!("rustc {}", string)
+    };
 
-    let host = env::var_os("HOST").expect("HOST not set");
-    if let Some("windows") = host.to_str().unwrap().split('-').nth(2) {
-        println!("cargo:rustc-cfg=host_os=\"windows\"");
+    match rustc::parse(&line) {
+        rustc::ParseResult::Success(version) => version,
+        rustc::ParseResult::OopsClippy
+        | rustc::ParseResult::OopsMirai
+        | rustc::ParseResult::Unrecognized => {
+            eprintln!(
+                "Error: unrecognized RUSTVERSION_OVERRIDE: {:?}\n\n\
+                Expected a version like `1.70.0`, `1.86.0-beta.4`, or \
+                `1.87.0-nightly (abc 2025-03-01)`",
+                string,
+            );
+            process::exit(1);
+        }
     }
 }
 
@@ -10,19 +10,25 @@ pub enum ParseResult {
 
 #[cfg_attr(test, derive(PartialEq))]
 pub struct Version {
+    pub major: u16,
     pub minor: u16,
     pub patch: u16,
     pub channel: Channel,
+    pub commit_hash: Option<String>,
+    pub commit_date: Option<Date>,
+    pub host: Option<String>,
+    pub llvm: Option<String>,
 }
 
 #[cfg_attr(test, derive(PartialEq))]
 pub enum Channel {
     Stable,
-    Beta,
+    Beta(Option<u16>),
     Nightly(Date),
     Dev,
 }
 
+#[derive(Copy, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct Date {
     pub year: u16,
@@ -31,8 +37,29 @@ pub struct Date {
 }
 
 pub fn parse(string: &str) -> ParseResult {
-    let last_line = string.lines().last().unwrap_or(string);
-    let mut words = last_line.trim().split(' ');
+    // Output of `rustc -vV` is the usual version line followed by key-value
+    // lines. Output of `rustc --version` is only the version line, possibly
+    // preceded by some junk printed by a wrapper.
+    let mut version_line = string;
+    let mut commit_hash = None;
+    let mut commit_date = None;
+    let mut host = None;
+    let mut llvm = None;
+    for line in string.lines() {
+        let mut key_value = line.trim().splitn(2, ": ");
+        let key = key_value.next().unwrap();
+        let value = key_value.next().filter(|value| *value != "unknown");
+        match (key, value) {
+            ("binary", _) | ("release", _) => {}
+            ("commit-hash", value) => commit_hash = value.map(str::to_owned),
+            ("commit-date", value) => commit_date = value.and_then(parse_date),
+            ("host", value) => host = value.map(str::to_owned),
+            ("LLVM version", value) => llvm = value.map(str::to_owned),
+            _ => version_line = line,
+        }
+    }
+
+    let mut words = version_line.trim().split(' ');
 
     match words.next() {
         Some("rustc") => {}
@@ -41,7 +68,18 @@ pub fn parse(string: &str) -> ParseResult {
         Some(_) | None => return ParseResult::Unrecognized,
     }
 
-    parse_words(&mut words).map_or(ParseResult::Unrecognized, ParseResult::Success)
+    match parse_words(&mut words) {
+        Some(mut version) => {
+            version.commit_hash = commit_hash;
+            if commit_date.is_some() {
+                version.commit_date = commit_date;
+            }
+            version.host = host;
+            version.llvm = llvm;
+            ParseResult::Success(version)
+        }
+        None => ParseResult::Unrecognized,
+    }
 }
 
 fn parse_words(words: &mut dyn Iterator<Item = &str>) -> Option<Version> {
@@ -50,29 +88,23 @@ fn parse_words(words: &mut dyn Iterator<Item = &str>) -> Option<Version> {
     let channel = version_channel.next();
 
     let mut digits = version.split('.');
-    let major = digits.next()?;
-    if major != "1" {
-        return None;
-    }
+    let major = digits.next()?.parse().ok()?;
     let minor = digits.next()?.parse().ok()?;
     let patch = digits.next().unwrap_or("0").parse().ok()?;
 
+    let mut commit_date = None;
     let channel = match channel {
         None => Stable,
         Some("dev") => Dev,
-        Some(channel) if channel.starts_with("beta") => Beta,
+        Some(channel) if channel.starts_with("beta.") => Beta(channel[5..].parse().ok()),
+        Some(channel) if channel.starts_with("beta") => Beta(None),
         Some("nightly") => match words.next() {
             Some(hash) if hash.starts_with('(') => match words.next() {
                 None if hash.ends_with(')') => Dev,
                 Some(date) if date.ends_with(')') => {
-                    let mut date = date[..date.len() - 1].split('-');
-                    let year = date.next()?.parse().ok()?;
-                    let month = date.next()?.parse().ok()?;
-                    let day = date.next()?.parse().ok()?;
-                    match date.next() {
-                        None => Nightly(Date { year, month, day }),
-                        Some(_) => return None,
-                    }
+                    let date = parse_date(&date[..date.len() - 1])?;
+                    commit_date = Some(date);
+                    Nightly(date)
                 }
                 None | Some(_) => return None,
             },
@@ -82,20 +114,52 @@ fn parse_words(words: &mut dyn Iterator<Item = &str>) -> Option<Version> {
         Some(_) => return None,
     };
 
+    // Stable, beta and dev compilers print the commit date too, as in
+    // `rustc 1.85.0-beta.3 (4ba4ac612 2025-01-20)`, but unlike for nightly,
+    // anything unexpected here is not a reason to reject the version.
+    if commit_date.is_none() {
+        if let (Some(hash), Some(date)) = (words.next(), words.next()) {
+            if hash.starts_with('(') && date.ends_with(')') {
+                commit_date = parse_date(&date[..date.len() - 1]);
+            }
+        }
+    }
+
     Some(Version {
+        major,
         minor,
         patch,
         channel,
+        commit_hash: None,
+        commit_date,
+        host: None,
+        llvm: None,
     })
 }
 
+fn parse_date(string: &str) -> Option<Date> {
+    let mut date = string.split('-');
+    let year = date.next()?.parse().ok()?;
+    let month = date.next()?.parse().ok()?;
+    let day = date.next()?.parse().ok()?;
+    match date.next() {
+        None => Some(Date { year, month, day }),
+        Some(_) => None,
+    }
+}
+
 impl Debug for Version {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter
             .debug_struct("crate::version::Version")
+            .field("major", &self.major)
             .field("minor", &self.minor)
             .field("patch", &self.patch)
             .field("channel", &self.channel)
+            .field("commit_hash", &self.commit_hash)
+            .field("commit_date", &self.commit_date)
+            .field("host", &self.host)
+            .field("llvm", &self.llvm)
             .finish()
     }
 }
@@ -104,7 +168,10 @@ impl Debug for Channel {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Channel::Stable => formatter.write_str("crate::version::Channel::Stable"),
-            Channel::Beta => formatter.write_str("crate::version::Channel::Beta"),
+            Channel::Beta(number) => formatter
+                .debug_tuple("crate::version::Channel::Beta")
+                .field(number)
+                .finish(),
             Channel::Nightly(date) => formatter
                 .debug_tuple("crate::version::Channel::Nightly")
                 .field(date)
@@ -1,35 +1,234 @@
 use crate::error::{Error, Result};
 use crate::expr::{self, Expr};
-use crate::{iter, token};
-use proc_macro::{Span, TokenStream};
+use crate::iter::{self, Iter};
+use crate::token;
+use proc_macro::{Delimiter, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::iter::FromIterator;
 
 pub struct Args {
     pub condition: Expr,
+    pub span: Span,
     pub then: Then,
+    pub otherwise: Option<Then>,
 }
 
 pub enum Then {
-    Const(Span),
+    // A qualifier like `const` or `extern "C"`, to insert on the item.
+    Qualifier(Vec<TokenTree>),
+    // A qualifier written as `!const`, to remove from the item.
+    NotQualifier(Ident),
     Attribute(TokenStream),
 }
 
 pub fn parse(input: TokenStream) -> Result<Args> {
     let ref mut input = iter::new(input);
+    let span = input.peek().map_or_else(Span::call_site, TokenTree::span);
     let condition = expr::parse(input)?;
 
     token::parse_punct(input, ',')?;
-    if input.peek().is_none() {
+    let then = parse_then(input)?;
+
+    let otherwise = match token::parse_optional_keyword(input, "else") {
+        Some(_) => {
+            token::parse_punct(input, '=')?;
+            let otherwise = parse_then(input)?;
+            token::parse_end(input)?;
+            Some(otherwise)
+        }
+        // An unsafe attribute like `unsafe(no_mangle)` falls back to the plain
+        // attribute on compilers that do not accept the unsafe form.
+        None => match then {
+            Then::Attribute(ref attrs) => unwrap_unsafe(attrs)?.map(Then::Attribute),
+            Then::Qualifier(_) | Then::NotQualifier(_) => None,
+        },
+    };
+
+    Ok(Args {
+        condition,
+        span,
+        then,
+        otherwise,
+    })
+}
+
+// Parses either a qualifier like `const` or `!const`, or one or more
+// attributes, up to an `else` or the end of the input.
+fn parse_then(input: Iter) -> Result<Then> {
+    if input.peek().is_none() || is_else(input) {
         return Err(Error::new(Span::call_site(), "expected one or more attrs"));
     }
 
-    let const_span = token::parse_optional_keyword(input, "const");
-    let then = if let Some(const_span) = const_span {
-        token::parse_optional_punct(input, ',');
-        token::parse_end(input)?;
-        Then::Const(const_span)
-    } else {
-        Then::Attribute(input.collect())
+    if token::parse_optional_punct(input, '!').is_some() {
+        return match input.next() {
+            Some(TokenTree::Ident(ref keyword)) if is_qualifier(keyword) => {
+                parse_keyword_end(input)?;
+                Ok(Then::NotQualifier(keyword.clone()))
+            }
+            unexpected => {
+                let span = unexpected
+                    .as_ref()
+                    .map_or_else(Span::call_site, TokenTree::span);
+                Err(Error::new(
+                    span,
+                    "expected `const`, `async`, `unsafe` or `extern` after `!`",
+                ))
+            }
+        };
+    }
+
+    let mut attrs = Vec::new();
+
+    let keyword = match input.peek() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => String::new(),
+    };
+    match keyword.as_str() {
+        "const" | "async" | "unsafe" | "extern" => {
+            let mut qualifier = vec![input.next().unwrap()];
+            match input.peek() {
+                // An unsafe attribute like `unsafe(no_mangle)`, rather than the
+                // `unsafe` qualifier.
+                Some(TokenTree::Group(group))
+                    if keyword == "unsafe" && group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    attrs.extend(qualifier);
+                }
+                Some(TokenTree::Literal(_)) if keyword == "extern" => {
+                    qualifier.extend(input.next());
+                    parse_keyword_end(input)?;
+                    return Ok(Then::Qualifier(qualifier));
+                }
+                _ => {
+                    parse_keyword_end(input)?;
+                    return Ok(Then::Qualifier(qualifier));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    while input.peek().is_some() && !is_else(input) {
+        attrs.extend(input.next());
+    }
+
+    // The comma before `else` separates the arms and is not part of the
+    // attributes.
+    if is_else(input) {
+        match attrs.pop() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            _ => {
+                let span = input.peek().unwrap().span();
+                return Err(Error::new(span, "expected `,` before `else`"));
+            }
+        }
+    }
+
+    Ok(Then::Attribute(TokenStream::from_iter(attrs)))
+}
+
+fn unwrap_unsafe(attrs: &TokenStream) -> Result<Option<TokenStream>> {
+    let mut attrs = attrs.clone().into_iter();
+    let (unsafe_token, group) = match (attrs.next(), attrs.next(), attrs.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)), None)
+            if ident.to_string() == "unsafe" && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            (ident, group)
+        }
+        _ => return Ok(None),
     };
 
-    Ok(Args { condition, then })
+    if group.stream().is_empty() {
+        return Err(Error::new(
+            unsafe_token.span(),
+            "expected an attribute inside `unsafe(...)`",
+        ));
+    }
+    Ok(Some(group.stream()))
+}
+
+fn parse_keyword_end(input: Iter) -> Result<()> {
+    token::parse_optional_punct(input, ',');
+    if is_else(input) {
+        Ok(())
+    } else {
+        token::parse_end(input)
+    }
+}
+
+fn is_qualifier(ident: &Ident) -> bool {
+    match ident.to_string().as_str() {
+        "const" | "async" | "unsafe" | "extern" => true,
+        _ => false,
+    }
+}
+
+fn is_else(input: Iter) -> bool {
+    match input.peek() {
+        Some(TokenTree::Ident(ident)) => ident.to_string() == "else",
+        _ => false,
+    }
+}
+
+pub struct Arm {
+    pub condition: Option<Expr>,
+    pub span: Span,
+    pub then: Then,
+}
+
+pub fn parse_match(input: TokenStream) -> Result<Vec<Arm>> {
+    // Attributes may themselves be separated by commas, so a comma only
+    // starts a new arm if it is followed by `=>` before the next comma.
+    let mut segments = vec![Vec::new()];
+    for token in iter::new(input) {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => segments.push(Vec::new()),
+            token => segments.last_mut().unwrap().push(token),
+        }
+    }
+    if segments.len() > 1 && segments.last().unwrap().is_empty() {
+        segments.pop();
+    }
+
+    let mut arms: Vec<(Option<Expr>, Span, Vec<TokenTree>)> = Vec::new();
+    for segment in segments {
+        if !arms.is_empty() && !has_fat_arrow(&segment) {
+            let &mut (_, _, ref mut attrs) = arms.last_mut().unwrap();
+            attrs.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+            attrs.extend(segment);
+            continue;
+        }
+
+        let span = segment
+            .first()
+            .map_or_else(Span::call_site, TokenTree::span);
+        let ref mut segment = iter::new(TokenStream::from_iter(segment));
+        let condition = if token::parse_optional_keyword(segment, "_").is_some() {
+            None
+        } else {
+            Some(expr::parse(segment)?)
+        };
+        token::parse_punct(segment, '=')?;
+        token::parse_punct(segment, '>')?;
+        arms.push((condition, span, segment.collect::<Vec<_>>()));
+    }
+
+    arms.into_iter().map(finish_arm).collect()
+}
+
+fn has_fat_arrow(segment: &[TokenTree]) -> bool {
+    segment.windows(2).any(|pair| match pair {
+        [TokenTree::Punct(eq), TokenTree::Punct(gt)] => eq.as_char() == '=' && gt.as_char() == '>',
+        _ => false,
+    })
+}
+
+fn finish_arm((condition, span, attrs): (Option<Expr>, Span, Vec<TokenTree>)) -> Result<Arm> {
+    let ref mut attrs = iter::new(TokenStream::from_iter(attrs));
+    let then = parse_then(attrs)?;
+    token::parse_end(attrs)?;
+    Ok(Arm {
+        condition,
+        span,
+        then,
+    })
 }
@@ -2,26 +2,63 @@ use crate::date::{self, Date};
 use crate::error::{Error, Result};
 use crate::iter::Iter;
 use crate::release::{self, Release};
-use crate::time;
 use crate::version::{Channel::*, Version};
+use crate::{expr, msrv, suggest, time, token};
 use proc_macro::{Group, TokenTree};
 use std::cmp::Ordering;
+use std::fmt::{self, Display};
 
+#[derive(Copy, Clone)]
 pub enum Bound {
     Nightly(Date),
     Stable(Release),
 }
 
+impl Display for Bound {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bound::Nightly(date) => Display::fmt(date, formatter),
+            Bound::Stable(release) => Display::fmt(release, formatter),
+        }
+    }
+}
+
 pub fn parse(paren: Group, iter: Iter) -> Result<Bound> {
-    if let Some(TokenTree::Literal(literal)) = iter.peek() {
-        let repr = literal.to_string();
-        if repr.starts_with(|ch: char| ch.is_ascii_digit()) {
-            if repr.contains('.') {
-                return release::parse(paren, iter).map(Bound::Stable);
-            } else {
-                return date::parse(paren, iter).map(Bound::Nightly);
+    if let Some(msrv) = token::parse_optional_keyword(iter, "msrv") {
+        return match msrv::get() {
+            Some(release) => Ok(Bound::Stable(release)),
+            None => Err(Error::new(
+                msrv,
+                "`msrv` requires the crate to declare a `rust-version` in Cargo.toml",
+            )),
+        };
+    }
+    match iter.peek() {
+        Some(TokenTree::Literal(literal)) => {
+            let repr = literal.to_string();
+            if repr.starts_with(|ch: char| ch.is_ascii_digit()) {
+                if repr.contains('.') {
+                    let release = release::parse(paren, iter)?;
+                    reject_channel(release, iter)?;
+                    return Ok(Bound::Stable(release));
+                } else {
+                    return date::parse(paren, iter).map(Bound::Nightly);
+                }
+            }
+        }
+        Some(TokenTree::Ident(ident)) => {
+            let name = ident.to_string();
+            if name == "rust_version" || suggest::similar(&name, &["msrv"]).is_some() {
+                let msg = format!("unknown bound `{}`, did you mean `msrv`?", name);
+                return Err(Error::new(ident.span(), msg));
             }
         }
+        Some(TokenTree::Punct(punct)) if expr::is_comparison(punct) => {
+            let punct = punct.clone();
+            iter.next();
+            return Err(expr::comparison(&punct, iter));
+        }
+        Some(TokenTree::Group(_)) | Some(TokenTree::Punct(_)) | None => {}
     }
     let msg = format!(
         "expected rustc release number like 1.85, or nightly date like {}",
@@ -30,15 +67,53 @@ pub fn parse(paren: Group, iter: Iter) -> Result<Bound> {
     Err(Error::group(paren, msg))
 }
 
+// A release number like `1.80.0-beta`: the channel is a separate selector.
+fn reject_channel(release: Release, iter: Iter) -> Result<()> {
+    let dash = match iter.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '-' => punct.span(),
+        _ => return Ok(()),
+    };
+    iter.next();
+    let channel = match iter.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        _ => return Err(Error::new(dash, "unexpected token")),
+    };
+
+    let release = format!("{}.{}", release.major, release.minor);
+    let msg = match channel.to_string().as_str() {
+        channel @ "beta" | channel @ "nightly" => format!(
+            "a release number does not include the channel; `since({0})` already includes {1} compilers of {0}, and `{1}({0})` matches only those",
+            release, channel,
+        ),
+        _ => "a release number does not include the channel".to_owned(),
+    };
+    Err(Error::new2(dash, channel.span(), msg))
+}
+
+// Ordering between two bounds of the same kind, or None if one is a release
+// and the other a date. A release without patch number compares like patch 0.
+pub fn compare(lhs: Bound, rhs: Bound) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Bound::Nightly(lhs), Bound::Nightly(rhs)) => Some(lhs.cmp(&rhs)),
+        (Bound::Stable(lhs), Bound::Stable(rhs)) => {
+            let lhs = (lhs.major, lhs.minor, lhs.patch.unwrap_or(0));
+            let rhs = (rhs.major, rhs.minor, rhs.patch.unwrap_or(0));
+            Some(lhs.cmp(&rhs))
+        }
+        (Bound::Nightly(_), Bound::Stable(_)) | (Bound::Stable(_), Bound::Nightly(_)) => None,
+    }
+}
+
 impl PartialEq<Bound> for Version {
     fn eq(&self, rhs: &Bound) -> bool {
         match rhs {
             Bound::Nightly(date) => match self.channel {
-                Stable | Beta | Dev => false,
+                Stable | Beta(_) | Dev => self.commit_date == Some(*date),
                 Nightly(nightly) => nightly == *date,
             },
             Bound::Stable(release) => {
-                self.minor == release.minor
+                self.major == release.major
+                    && self.minor == release.minor
                     && release.patch.map_or(true, |patch| self.patch == patch)
             }
         }
@@ -48,14 +123,19 @@ impl PartialEq<Bound> for Version {
 impl PartialOrd<Bound> for Version {
     fn partial_cmp(&self, rhs: &Bound) -> Option<Ordering> {
         match rhs {
-            Bound::Nightly(date) => match self.channel {
-                Stable | Beta => Some(Ordering::Less),
-                Nightly(nightly) => Some(nightly.cmp(date)),
-                Dev => Some(Ordering::Greater),
+            Bound::Nightly(date) => match (self.channel, self.commit_date) {
+                (Nightly(nightly), _) => Some(nightly.cmp(date)),
+                (Stable, Some(commit_date))
+                | (Beta(_), Some(commit_date))
+                | (Dev, Some(commit_date)) => Some(commit_date.cmp(date)),
+                (Stable, None) | (Beta(_), None) => Some(Ordering::Less),
+                (Dev, None) => Some(Ordering::Greater),
             },
             Bound::Stable(release) => {
-                let version = (self.minor, self.patch);
-                let bound = (release.minor, release.patch.unwrap_or(0));
+                // A release without patch number is equal to every patch of
+                // that release, consistent with PartialEq.
+                let version = (self.major, self.minor, release.patch.map(|_| self.patch));
+                let bound = (release.major, release.minor, release.patch);
                 Some(version.cmp(&bound))
             }
         }
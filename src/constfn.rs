@@ -1,10 +1,14 @@
 use crate::error::{Error, Result};
-use proc_macro::{Ident, Span, TokenStream, TokenTree};
-use std::iter;
+use crate::expand;
+use crate::iter::Iter;
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use std::iter::{self, FromIterator};
+use std::mem;
 
 #[derive(PartialOrd, PartialEq)]
 enum Qualifiers {
     None,
+    Const,
     Async,
     Unsafe,
     Extern,
@@ -14,30 +18,67 @@ enum Qualifiers {
 impl Qualifiers {
     fn from_ident(ident: &Ident) -> Self {
         match ident.to_string().as_str() {
+            "const" => Qualifiers::Const,
             "async" => Qualifiers::Async,
             "unsafe" => Qualifiers::Unsafe,
             "extern" => Qualifiers::Extern,
             _ => Qualifiers::None,
         }
     }
+
+    fn from_token(token: &TokenTree) -> Self {
+        match token {
+            TokenTree::Ident(ident) => Qualifiers::from_ident(ident),
+            TokenTree::Literal(_) => Qualifiers::Abi,
+            TokenTree::Group(_) | TokenTree::Punct(_) => Qualifiers::None,
+        }
+    }
 }
 
-pub(crate) fn insert_const(input: TokenStream, const_span: Span) -> Result<TokenStream> {
+enum Kind {
+    Fn,
+    Impl,
+    ExternBlock,
+}
+
+// An item split around the qualifiers in front of its `fn`, `impl`, or extern
+// block body.
+struct Item {
+    before: TokenStream,
+    qualifiers: Vec<TokenTree>,
+    kind: Kind,
+    rest: TokenStream,
+}
+
+fn split(input: TokenStream) -> Option<Item> {
     let ref mut input = crate::iter::new(input);
-    let mut out = TokenStream::new();
+    let mut before = TokenStream::new();
     let mut qualifiers = Qualifiers::None;
     let mut pending = Vec::new();
 
     while let Some(token) = input.next() {
-        match token {
-            TokenTree::Ident(ref ident) if ident.to_string() == "fn" => {
-                let const_ident = Ident::new("const", const_span);
-                out.extend(iter::once(TokenTree::Ident(const_ident)));
-                out.extend(pending);
-                out.extend(iter::once(token));
-                out.extend(input);
-                return Ok(out);
+        let kind = match token {
+            TokenTree::Ident(ref ident) if ident.to_string() == "fn" => Some(Kind::Fn),
+            TokenTree::Ident(ref ident) if ident.to_string() == "impl" => Some(Kind::Impl),
+            TokenTree::Group(ref group)
+                if group.delimiter() == Delimiter::Brace && qualifiers >= Qualifiers::Extern =>
+            {
+                Some(Kind::ExternBlock)
             }
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let mut rest = TokenStream::from(token);
+            rest.extend(input);
+            return Some(Item {
+                before,
+                qualifiers: pending,
+                kind,
+                rest,
+            });
+        }
+
+        match token {
             TokenTree::Ident(ref ident) if Qualifiers::from_ident(ident) > qualifiers => {
                 qualifiers = Qualifiers::from_ident(ident);
                 pending.push(token);
@@ -48,11 +89,234 @@ pub(crate) fn insert_const(input: TokenStream, const_span: Span) -> Result<Token
             }
             _ => {
                 qualifiers = Qualifiers::None;
-                out.extend(pending.drain(..));
-                out.extend(iter::once(token));
+                before.extend(pending.drain(..));
+                before.extend(iter::once(token));
             }
         }
     }
 
-    Err(Error::new(const_span, "only allowed on a fn item"))
+    None
+}
+
+pub(crate) fn insert_const(input: TokenStream, const_span: Span) -> Result<TokenStream> {
+    let const_token = TokenTree::Ident(Ident::new("const", const_span));
+    insert_qualifier(input, vec![const_token])
+}
+
+// Inserts a qualifier, like `const` or `extern "C"`, in its place among the
+// qualifiers the item already has.
+pub(crate) fn insert_qualifier(
+    input: TokenStream,
+    qualifier: Vec<TokenTree>,
+) -> Result<TokenStream> {
+    let keyword = match qualifier[0] {
+        TokenTree::Ident(ref keyword) => keyword.clone(),
+        _ => unreachable!(),
+    };
+    let rank = Qualifiers::from_ident(&keyword);
+
+    let mut item = match split(input) {
+        Some(item) => item,
+        None => return Err(not_allowed(&keyword, true)),
+    };
+
+    match item.kind {
+        Kind::Fn => {}
+        Kind::Impl if rank == Qualifiers::Const => {
+            let mut out = item.before;
+            out.extend(item.qualifiers);
+            return insert_const_impl(out, &mut crate::iter::new(item.rest), keyword.span());
+        }
+        Kind::ExternBlock if rank == Qualifiers::Unsafe => {}
+        Kind::Impl | Kind::ExternBlock => return Err(not_allowed(&keyword, true)),
+    }
+
+    for existing in &item.qualifiers {
+        if Qualifiers::from_token(existing) == rank {
+            let msg = format!("this item is already {}", keyword);
+            return Err(Error::new(existing.span(), msg));
+        }
+    }
+
+    let position = item
+        .qualifiers
+        .iter()
+        .position(|existing| Qualifiers::from_token(existing) > rank)
+        .unwrap_or(item.qualifiers.len());
+    let after = item.qualifiers.split_off(position);
+
+    let mut out = item.before;
+    out.extend(item.qualifiers);
+    out.extend(qualifier);
+    out.extend(after);
+    out.extend(item.rest);
+    Ok(out)
+}
+
+// Removes a qualifier that the item has, like the `const` of a const fn.
+pub(crate) fn remove_qualifier(input: TokenStream, keyword: Ident) -> Result<TokenStream> {
+    let rank = Qualifiers::from_ident(&keyword);
+
+    let mut item = match split(input) {
+        Some(item) => item,
+        None => return Err(not_allowed(&keyword, false)),
+    };
+
+    let item_span = match item.kind {
+        Kind::Fn => item.rest.clone().into_iter().next().unwrap().span(),
+        Kind::ExternBlock if rank == Qualifiers::Unsafe => item.qualifiers[0].span(),
+        Kind::Impl | Kind::ExternBlock => return Err(not_allowed(&keyword, false)),
+    };
+
+    let position = item
+        .qualifiers
+        .iter()
+        .position(|existing| Qualifiers::from_token(existing) == rank)
+        .ok_or_else(|| Error::new(item_span, format!("this item is not {}", keyword)))?;
+
+    let mut after = item.qualifiers.split_off(position + 1);
+    item.qualifiers.pop();
+    // The ABI, if any, goes away together with `extern`.
+    if rank == Qualifiers::Extern {
+        after.clear();
+    }
+
+    let mut out = item.before;
+    out.extend(item.qualifiers);
+    out.extend(after);
+    out.extend(item.rest);
+    Ok(out)
+}
+
+fn not_allowed(keyword: &Ident, impl_allowed: bool) -> Error {
+    let msg = match keyword.to_string().as_str() {
+        "const" if impl_allowed => "only allowed on a fn item or an inherent impl block",
+        "unsafe" => "only allowed on a fn item or an extern block",
+        _ => "only allowed on a fn item",
+    };
+    Error::new(keyword.span(), msg)
+}
+
+// Inserts `const` on every method of an inherent impl block, other than those
+// that are already const or marked #[rustversion::not_const].
+fn insert_const_impl(mut out: TokenStream, input: Iter, const_span: Span) -> Result<TokenStream> {
+    let mut after_where = false;
+
+    while let Some(token) = input.next() {
+        match token {
+            TokenTree::Ident(ref ident) if ident.to_string() == "where" => after_where = true,
+            // `impl Trait for Type`, as opposed to `where for<'a> ...`.
+            TokenTree::Ident(ref ident) if ident.to_string() == "for" && !after_where => {
+                match input.peek() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
+                    _ => {
+                        return Err(Error::new(
+                            ident.span(),
+                            "methods of a trait impl cannot be const",
+                        ));
+                    }
+                }
+            }
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+                // Inner attributes of the impl block are not part of a method.
+                let mut tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                let inner_attrs = expand::inner_attrs_len(&tokens);
+                let mut body: TokenStream = tokens.drain(..inner_attrs).collect();
+                let mut item = Vec::new();
+                for token in crate::iter::new(tokens.into_iter().collect()) {
+                    let end_of_item = match token {
+                        TokenTree::Punct(ref punct) => punct.as_char() == ';',
+                        TokenTree::Group(ref group) => group.delimiter() == Delimiter::Brace,
+                        TokenTree::Ident(_) | TokenTree::Literal(_) => false,
+                    };
+                    item.push(token);
+                    if end_of_item {
+                        body.extend(insert_const_method(
+                            mem::replace(&mut item, Vec::new()),
+                            const_span,
+                        )?);
+                    }
+                }
+                body.extend(item);
+
+                let mut group = Group::new(Delimiter::Brace, body);
+                group.set_span(token.span());
+                out.extend(iter::once(TokenTree::Group(group)));
+                out.extend(input);
+                return Ok(out);
+            }
+            _ => {}
+        }
+        out.extend(iter::once(token));
+    }
+
+    Err(Error::new(
+        const_span,
+        "only allowed on a fn item or an inherent impl block",
+    ))
+}
+
+fn insert_const_method(item: Vec<TokenTree>, const_span: Span) -> Result<TokenStream> {
+    let mut out = TokenStream::new();
+    let mut item = item.into_iter().peekable();
+
+    let mut not_const = false;
+    loop {
+        match item.peek() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {}
+            _ => break,
+        }
+        let pound = item.next();
+        let attr = item.next();
+        match attr {
+            Some(TokenTree::Group(ref group)) if is_not_const(group) => not_const = true,
+            _ => {
+                out.extend(pound);
+                out.extend(attr);
+            }
+        }
+    }
+
+    let item: Vec<TokenTree> = item.collect();
+    let mut is_fn = false;
+    let mut is_const = false;
+    let mut async_span = None;
+    for token in &item {
+        match token {
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                "fn" => {
+                    is_fn = true;
+                    break;
+                }
+                "const" => is_const = true,
+                "async" => async_span = Some(ident.span()),
+                "pub" | "crate" | "default" | "unsafe" | "extern" => {}
+                _ => break,
+            },
+            // pub(crate), extern "C"
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {}
+            TokenTree::Literal(_) => {}
+            TokenTree::Group(_) | TokenTree::Punct(_) => break,
+        }
+    }
+
+    let item = TokenStream::from_iter(item);
+    if !is_fn || is_const || not_const {
+        out.extend(item);
+        return Ok(out);
+    }
+    if let Some(async_span) = async_span {
+        return Err(Error::new(async_span, "an async fn cannot be const"));
+    }
+    out.extend(insert_const(item, const_span)?);
+    Ok(out)
+}
+
+fn is_not_const(attr: &Group) -> bool {
+    let path: String = attr
+        .stream()
+        .into_iter()
+        .map(|token| token.to_string())
+        .collect();
+    path == "rustversion::not_const" || path == "::rustversion::not_const"
 }
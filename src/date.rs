@@ -1,7 +1,7 @@
 use crate::error::{Error, Result};
 use crate::iter::Iter;
 use crate::{time, token};
-use proc_macro::Group;
+use proc_macro::{Group, Span};
 use std::fmt::{self, Display};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -21,30 +21,72 @@ impl Display for Date {
     }
 }
 
+// Nightlies identifying themselves as Rust 1.x, which is what a version
+// string must say for rustversion to parse it, date from 2015 onward.
+const FIRST_NIGHTLY_YEAR: u64 = 2015;
+
 pub fn parse(paren: Group, iter: Iter) -> Result<Date> {
-    try_parse(iter).map_err(|()| {
+    let (year, month, day) = try_parse(iter).map_err(|()| {
         let msg = format!("expected nightly date, like {}", time::today());
         Error::group(paren, msg)
+    })?;
+
+    if year.value < FIRST_NIGHTLY_YEAR {
+        let msg = format!(
+            "invalid year {} in nightly date, the first dated nightly is from {}",
+            year.value, FIRST_NIGHTLY_YEAR,
+        );
+        return Err(Error::new(year.span, msg));
+    }
+    if year.value >= 3000 {
+        let msg = format!("invalid year {} in nightly date", year.value);
+        return Err(Error::new(year.span, msg));
+    }
+    let year_value = year.value as u16;
+
+    if month.value < 1 || month.value > 12 {
+        let msg = format!(
+            "invalid month {} in nightly date, expected 1 through 12",
+            month.value,
+        );
+        return Err(Error::new(month.span, msg));
+    }
+    let month_value = month.value as u8;
+
+    let days_in_month = time::days_in_month(year_value, month_value);
+    if day.value < 1 || day.value > u64::from(days_in_month) {
+        let msg = format!(
+            "invalid day {} in nightly date, {:04}-{:02} has {} days",
+            day.value, year_value, month_value, days_in_month,
+        );
+        return Err(Error::new(day.span, msg));
+    }
+
+    Ok(Date {
+        year: year_value,
+        month: month_value,
+        day: day.value as u8,
     })
 }
 
-fn try_parse(iter: Iter) -> Result<Date, ()> {
-    let year = token::parse_literal(iter).map_err(drop)?;
+struct Component {
+    value: u64,
+    span: Span,
+}
+
+fn try_parse(iter: Iter) -> Result<(Component, Component, Component), ()> {
+    let year = parse_component(iter)?;
     token::parse_punct(iter, '-').map_err(drop)?;
-    let month = token::parse_literal(iter).map_err(drop)?;
+    let month = parse_component(iter)?;
     token::parse_punct(iter, '-').map_err(drop)?;
-    let day = token::parse_literal(iter).map_err(drop)?;
-
-    let year = year.to_string().parse::<u64>().map_err(drop)?;
-    let month = month.to_string().parse::<u64>().map_err(drop)?;
-    let day = day.to_string().parse::<u64>().map_err(drop)?;
-    if year >= 3000 || month > 12 || day > 31 {
-        return Err(());
-    }
+    let day = parse_component(iter)?;
+    Ok((year, month, day))
+}
 
-    Ok(Date {
-        year: year as u16,
-        month: month as u8,
-        day: day as u8,
+fn parse_component(iter: Iter) -> Result<Component, ()> {
+    let literal = token::parse_literal(iter).map_err(drop)?;
+    Ok(Component {
+        value: literal.to_string().parse().map_err(drop)?,
+        span: literal.span(),
     })
 }
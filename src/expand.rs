@@ -1,7 +1,12 @@
 use crate::attr::{self, Then};
+use crate::bound::Bound;
 use crate::error::{Error, Result};
-use crate::{constfn, expr, iter, token};
-use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use crate::expr::{Expr, Residual};
+use crate::release::Release;
+use crate::select::Arm;
+use crate::version::Version;
+use crate::{constfn, expire, expr, iter, msrv, token};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 use std::iter::FromIterator;
 
 pub fn cfg(introducer: &str, args: TokenStream, input: TokenStream) -> TokenStream {
@@ -10,6 +15,11 @@ pub fn cfg(introducer: &str, args: TokenStream, input: TokenStream) -> TokenStre
 
 fn try_cfg(introducer: &str, args: TokenStream, input: TokenStream) -> Result<TokenStream> {
     let introducer = Ident::new(introducer, Span::call_site());
+    let span = args
+        .clone()
+        .into_iter()
+        .next()
+        .map_or_else(Span::call_site, |token| token.span());
 
     let mut full_args = TokenStream::from(TokenTree::Ident(introducer));
     if !args.is_empty() {
@@ -23,54 +33,523 @@ fn try_cfg(introducer: &str, args: TokenStream, input: TokenStream) -> Result<To
     let expr = expr::parse(full_args)?;
     token::parse_end(full_args)?;
 
-    if expr.eval(crate::RUSTVERSION) {
-        Ok(allow_incompatible_msrv(input))
+    let item = input.clone();
+    let output = match expr.eval(crate::RUSTVERSION) {
+        Residual::Bool(true) => kept(&expr, input),
+        Residual::Cfg(predicate) => with_cfg(predicate, kept(&expr, input)),
+        // In documentation mode, the item is kept for rustdoc only, so that
+        // the documentation shows the APIs of every compiler and not just the
+        // one building it. Alternatives sharing a name are written with
+        // select!, which documents only the one selected by that compiler.
+        Residual::Bool(false) if cfg!(docs_mode) => {
+            let doc = TokenStream::from(TokenTree::Ident(Ident::new("doc", Span::call_site())));
+            with_cfg(doc, doc_note(&expr, input))
+        }
+        Residual::Bool(false) => TokenStream::new(),
+    };
+
+    Ok(warn_if_redundant(&expr, span, &item, output))
+}
+
+fn kept(expr: &Expr, input: TokenStream) -> TokenStream {
+    clippy_msrv(expr, doc_note(expr, input))
+}
+
+pub fn try_attr(args: attr::Args, input: TokenStream) -> Result<TokenStream> {
+    let item = input.clone();
+    let output = attr_output(&args.condition, args.then, args.otherwise, input)?;
+    Ok(warn_if_redundant(&args.condition, args.span, &item, output))
+}
+
+// The expansion of #[rustversion::attr] without the warning for a redundant
+// condition, for places like struct fields where a macro call cannot go.
+pub fn attr_output(
+    condition: &Expr,
+    then: Then,
+    otherwise: Option<Then>,
+    input: TokenStream,
+) -> Result<TokenStream> {
+    // Only the attribute depends on the condition. The item is compiled on
+    // every compiler, so #[clippy::msrv] from the condition would not apply.
+    Ok(match condition.eval(crate::RUSTVERSION) {
+        Residual::Bool(true) => {
+            let output = apply(then, input)?;
+            allow_incompatible_msrv(output)
+        }
+        Residual::Bool(false) => match otherwise {
+            Some(otherwise) => apply(otherwise, input)?,
+            None => input,
+        },
+        Residual::Cfg(predicate) => {
+            let output = apply_cfg(predicate, then, otherwise, input)?;
+            allow_incompatible_msrv(output)
+        }
+    })
+}
+
+pub fn attr_match(arms: Vec<attr::Arm>, input: TokenStream) -> Result<TokenStream> {
+    for arm in arms {
+        let condition = match arm.condition {
+            Some(condition) => condition,
+            None => return apply(arm.then, input),
+        };
+        match condition.eval(crate::RUSTVERSION) {
+            Residual::Bool(true) => {
+                let output = apply(arm.then, input)?;
+                return Ok(allow_incompatible_msrv(output));
+            }
+            Residual::Bool(false) => {}
+            Residual::Cfg(_) => {
+                return Err(Error::new(
+                    arm.span,
+                    "cfg(...) is not supported in attr_match, because the arm must be chosen before cfg is evaluated",
+                ));
+            }
+        }
+    }
+    Ok(input)
+}
+
+fn apply(then: Then, input: TokenStream) -> Result<TokenStream> {
+    match then {
+        Then::Qualifier(qualifier) => constfn::insert_qualifier(input, qualifier),
+        Then::NotQualifier(keyword) => constfn::remove_qualifier(input, keyword),
+        Then::Attribute(then) => Ok(cfg_attr(expr::compound("all", Vec::new()), then, input)),
+    }
+}
+
+// Leaves the choice between the two arms to the cfg predicate that remains
+// after evaluating the version selectors.
+fn apply_cfg(
+    predicate: TokenStream,
+    then: Then,
+    otherwise: Option<Then>,
+    input: TokenStream,
+) -> Result<TokenStream> {
+    let not_predicate = expr::compound("not", vec![predicate.clone()]);
+    match (then, otherwise) {
+        (Then::Attribute(then), None) => Ok(cfg_attr(predicate, then, input)),
+        (Then::Attribute(then), Some(Then::Attribute(otherwise))) => {
+            let input = cfg_attr(not_predicate, otherwise, input);
+            Ok(cfg_attr(predicate, then, input))
+        }
+        (then, otherwise) => {
+            // Inserting a qualifier changes the item itself, so emit one copy
+            // of the item per arm.
+            // #[cfg(#predicate)] ...
+            // #[cfg(not(#predicate))] ...
+            let otherwise = match otherwise {
+                Some(otherwise) => apply(otherwise, input.clone())?,
+                None => input.clone(),
+            };
+            let mut output = with_cfg(predicate, apply(then, input)?);
+            output.extend(with_cfg(not_predicate, otherwise));
+            Ok(output)
+        }
+    }
+}
+
+pub fn select(arms: Vec<Arm>) -> Result<TokenStream> {
+    for arm in arms {
+        let matches = match arm.condition {
+            Some(condition) => match condition.eval(crate::RUSTVERSION) {
+                Residual::Bool(matches) => matches,
+                Residual::Cfg(_) => {
+                    return Err(Error::new(
+                        arm.span,
+                        "cfg(...) is not supported in select!, because the arm must be chosen before cfg is evaluated",
+                    ));
+                }
+            },
+            None => true,
+        };
+        if matches {
+            return Ok(arm.body);
+        }
+    }
+    Ok(TokenStream::new())
+}
+
+pub fn remove_after(args: expire::Args, input: TokenStream) -> TokenStream {
+    // The crate's rust-version is the same for everyone who builds the crate.
+    // The compiler is not, so checking it fails the build of whoever upgrades
+    // their compiler, and happens only when asked for as compiler(...).
+    let (expired, what) = if args.compiler {
+        (crate::RUSTVERSION >= args.bound, "the compiler")
+    } else if let Some(msrv) = msrv::get() {
+        (
+            Version::from_release(msrv) >= args.bound,
+            "the crate's rust-version",
+        )
     } else {
-        Ok(TokenStream::new())
+        let msg = format!(
+            "remove_after requires the crate to declare a `rust-version` in Cargo.toml, or write compiler({}) to check the compiler building the crate instead",
+            args.bound,
+        );
+        let mut output = Error::new(args.span, msg).into_compile_error();
+        output.extend(input);
+        return output;
+    };
+
+    if !expired {
+        return input;
     }
+
+    let msg = format!(
+        "this item is marked for removal once {} reaches {}",
+        what, args.bound,
+    );
+    let mut output = match args.message {
+        Some(message) => message_error(args.span, msg, message),
+        None => Error::new(args.span, msg).into_compile_error(),
+    };
+    output.extend(input);
+    output
 }
 
-pub fn try_attr(args: attr::Args, input: TokenStream) -> Result<TokenStream> {
-    if !args.condition.eval(crate::RUSTVERSION) {
-        return Ok(input);
+// compile_error! { concat!(#msg, ": ", #message) }
+fn message_error(span: Span, msg: String, message: Literal) -> TokenStream {
+    let mut args = TokenStream::new();
+    args.extend(vec![
+        TokenTree::Literal(Literal::string(&msg)),
+        TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        TokenTree::Literal(Literal::string(": ")),
+        TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        TokenTree::Literal(message),
+    ]);
+    let concat = vec![
+        TokenTree::Ident(Ident::new("concat", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, args)),
+    ];
+    let mut group = Group::new(Delimiter::Brace, concat.into_iter().collect());
+    group.set_span(span);
+    let mut output = TokenStream::new();
+    output.extend(vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(group),
+    ]);
+    output
+}
+
+// If the crate's rust-version makes the condition always true or always false,
+// emit a use of a deprecated constant so that the user sees a warning pointing
+// at the condition. The item itself keeps its path and nesting.
+fn warn_if_redundant(
+    expr: &Expr,
+    span: Span,
+    item: &TokenStream,
+    output: TokenStream,
+) -> TokenStream {
+    let msrv = match msrv::get() {
+        Some(msrv) => msrv,
+        None => return output,
+    };
+
+    // Conditions that do not depend on the rust-version, like `all()`, are
+    // assumed to be intentional.
+    let oldest = Release {
+        major: 1,
+        minor: 0,
+        patch: None,
+    };
+    if expr.eval_msrv(oldest).is_some() {
+        return output;
     }
 
-    let output = match args.then {
-        Then::Const(const_token) => constfn::insert_const(input, const_token)?,
-        Then::Attribute(then) => {
-            TokenStream::from_iter(
-                // #[cfg_attr(all(), #then)]
-                vec![
-                    TokenTree::Punct(Punct::new('#', Spacing::Alone)),
-                    TokenTree::Group(Group::new(
-                        Delimiter::Bracket,
-                        TokenStream::from_iter(vec![
-                            TokenTree::Ident(Ident::new("cfg_attr", Span::call_site())),
-                            TokenTree::Group(Group::new(
-                                Delimiter::Parenthesis,
-                                TokenStream::from_iter(
-                                    vec![
-                                        TokenTree::Ident(Ident::new("all", Span::call_site())),
-                                        TokenTree::Group(Group::new(
-                                            Delimiter::Parenthesis,
-                                            TokenStream::new(),
-                                        )),
-                                        TokenTree::Punct(Punct::new(',', Spacing::Alone)),
-                                    ]
-                                    .into_iter()
-                                    .chain(then),
-                                ),
-                            )),
-                        ]),
-                    )),
-                ]
-                .into_iter()
-                .chain(input),
-            )
+    let always = match expr.eval_msrv(msrv) {
+        Some(true) => "true",
+        Some(false) => "false",
+        None => return output,
+    };
+    let msg = format!(
+        "this rustversion condition is always {} on the compilers allowed by the crate's rust-version",
+        always,
+    );
+
+    match placement(item) {
+        Placement::Beside => {
+            let mut output = output;
+            output.extend(deprecated_use(&msg, span));
+            output
+        }
+        Placement::InBody => {
+            let mut output: Vec<TokenTree> = output.into_iter().collect();
+            match output.pop() {
+                Some(TokenTree::Group(ref body)) if body.delimiter() == Delimiter::Brace => {
+                    // Inner attributes must stay first in the body.
+                    let mut rest: Vec<TokenTree> = body.stream().into_iter().collect();
+                    let inner_attrs = inner_attrs_len(&rest);
+                    let mut stream: TokenStream = rest.drain(..inner_attrs).collect();
+                    stream.extend(deprecated_use(&msg, span));
+                    stream.extend(rest);
+                    let mut group = Group::new(Delimiter::Brace, stream);
+                    group.set_span(body.span());
+                    output.push(TokenTree::Group(group));
+                }
+                // The fn was removed, and an associated fn has no place
+                // beside it for the warning.
+                last => output.extend(last),
+            }
+            output.into_iter().collect()
+        }
+        Placement::Nowhere => output,
+    }
+}
+
+enum Placement {
+    // Next to the item, where only a module or block can contain it.
+    Beside,
+    // Inside the body of a fn, which may be an associated fn.
+    InBody,
+    // Items that may be associated consts or types, or fns without a body,
+    // have no place for the warning that is valid in every impl, trait and
+    // extern block.
+    Nowhere,
+}
+
+fn placement(item: &TokenStream) -> Placement {
+    let tokens: Vec<TokenTree> = iter::new(item.clone()).collect();
+    let mut i = 0;
+
+    // #[attr]
+    while let Some(TokenTree::Punct(punct)) = tokens.get(i) {
+        if punct.as_char() != '#' {
+            break;
+        }
+        i += 2;
+    }
+
+    // pub, pub(crate)
+    if let Some(TokenTree::Ident(ident)) = tokens.get(i) {
+        if ident.to_string() == "pub" {
+            i += 1;
+            if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    while let Some(token) = tokens.get(i) {
+        match token {
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                "const" | "async" | "unsafe" | "safe" | "extern" | "default" => {}
+                "fn" => {
+                    return match tokens.last() {
+                        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => {
+                            Placement::InBody
+                        }
+                        _ => Placement::Nowhere,
+                    };
+                }
+                // A static in an extern block has no initializer.
+                "static" if !tokens.iter().any(is_eq) => return Placement::Nowhere,
+                "struct" | "enum" | "union" | "trait" | "mod" | "use" | "static" | "impl"
+                | "macro_rules" | "crate" => return Placement::Beside,
+                _ => return Placement::Nowhere,
+            },
+            // extern "C"
+            TokenTree::Literal(_) => {}
+            // extern "C" { ... }
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                return Placement::Beside;
+            }
+            TokenTree::Group(_) | TokenTree::Punct(_) => return Placement::Nowhere,
+        }
+        i += 1;
+    }
+
+    Placement::Nowhere
+}
+
+// Number of tokens taken by the #![attr] at the start of a body.
+pub fn inner_attrs_len(tokens: &[TokenTree]) -> usize {
+    let mut i = 0;
+    while let (Some(TokenTree::Punct(pound)), Some(TokenTree::Punct(bang))) =
+        (tokens.get(i), tokens.get(i + 1))
+    {
+        if pound.as_char() != '#' || bang.as_char() != '!' {
+            break;
         }
+        i += 3;
+    }
+    i.min(tokens.len())
+}
+
+fn is_eq(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Punct(punct) => punct.as_char() == '=',
+        _ => false,
+    }
+}
+
+// const _: () = {
+//     #[deprecated = #msg]
+//     const RUSTVERSION_CONDITION: () = ();
+//     RUSTVERSION_CONDITION
+// };
+fn deprecated_use(msg: &str, span: Span) -> TokenStream {
+    let unit = || TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new()));
+    let block = TokenStream::from_iter(vec![
+        TokenTree::Punct(Punct::new('#', Spacing::Alone)),
+        TokenTree::Group(Group::new(
+            Delimiter::Bracket,
+            TokenStream::from_iter(vec![
+                TokenTree::Ident(Ident::new("deprecated", Span::call_site())),
+                TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+                TokenTree::Literal(Literal::string(msg)),
+            ]),
+        )),
+        TokenTree::Ident(Ident::new("const", Span::call_site())),
+        TokenTree::Ident(Ident::new("RUSTVERSION_CONDITION", Span::call_site())),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        unit(),
+        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+        unit(),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("RUSTVERSION_CONDITION", span)),
+    ]);
+    TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("const", Span::call_site())),
+        TokenTree::Ident(Ident::new("_", Span::call_site())),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        unit(),
+        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Brace, block)),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+    ])
+}
+
+// Append a note about the condition to the item's documentation, for crates
+// documented with `--cfg rustversion_doc_notes`.
+fn doc_note(expr: &Expr, input: TokenStream) -> TokenStream {
+    let requirement = match expr {
+        Expr::Since(Bound::Stable(release)) => format!("Rust {}+", release),
+        _ => format!("rustc `{}`", expr),
+    };
+    let note = format!("\n\nAvailable on **{}** only.", requirement);
+
+    // doc = #note
+    let mut attr = TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("doc", Span::call_site())),
+        TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+        TokenTree::Literal(Literal::string(&note)),
+    ]);
+    // In documentation mode, every item documented by rustdoc gets the note,
+    // whether or not its condition is true. Otherwise only crates documented
+    // with --cfg rustversion_doc_notes do.
+    // Only rustdoc looks at that cfg, so only crates that are documented need
+    // to declare it to the unexpected_cfgs lint.
+    if !cfg!(docs_mode) {
+        attr = cfg_attr_tokens(
+            TokenStream::from(TokenTree::Ident(Ident::new(
+                "rustversion_doc_notes",
+                Span::call_site(),
+            ))),
+            attr,
+        );
+    }
+
+    // Insert after the item's existing attributes so that the note comes
+    // after its doc comment.
+    let mut output = TokenStream::new();
+    let mut input = input.into_iter().peekable();
+    loop {
+        match input.peek() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {}
+            _ => break,
+        }
+        output.extend(input.next());
+        output.extend(input.next());
+    }
+    let doc = TokenStream::from(TokenTree::Ident(Ident::new("doc", Span::call_site())));
+    output.extend(cfg_attr(doc, attr, input.collect()));
+    output
+}
+
+// cfg_attr(#predicate, #attr)
+fn cfg_attr_tokens(predicate: TokenStream, attr: TokenStream) -> TokenStream {
+    let mut args = predicate;
+    args.extend(std::iter::once(TokenTree::Punct(Punct::new(
+        ',',
+        Spacing::Alone,
+    ))));
+    args.extend(attr);
+    TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("cfg_attr", Span::call_site())),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, args)),
+    ])
+}
+
+fn cfg_attr(predicate: TokenStream, then: TokenStream, input: TokenStream) -> TokenStream {
+    TokenStream::from_iter(
+        // #[cfg_attr(#predicate, #then)]
+        vec![
+            TokenTree::Punct(Punct::new('#', Spacing::Alone)),
+            TokenTree::Group(Group::new(
+                Delimiter::Bracket,
+                cfg_attr_tokens(predicate, then),
+            )),
+        ]
+        .into_iter()
+        .chain(input),
+    )
+}
+
+pub fn with_cfg(predicate: TokenStream, input: TokenStream) -> TokenStream {
+    TokenStream::from_iter(
+        // #[cfg(#predicate)]
+        vec![
+            TokenTree::Punct(Punct::new('#', Spacing::Alone)),
+            TokenTree::Group(Group::new(
+                Delimiter::Bracket,
+                TokenStream::from_iter(vec![
+                    TokenTree::Ident(Ident::new("cfg", Span::call_site())),
+                    TokenTree::Group(Group::new(Delimiter::Parenthesis, predicate)),
+                ]),
+            )),
+        ]
+        .into_iter()
+        .chain(input),
+    )
+}
+
+// When the condition guarantees a minimum release, let Clippy's MSRV-aware
+// lints check the item against that release. Otherwise there is no telling
+// which APIs the item is allowed to use, so silence incompatible_msrv.
+fn clippy_msrv(expr: &Expr, input: TokenStream) -> TokenStream {
+    let implied = match expr.implied_msrv() {
+        Some(implied) => implied,
+        None => return allow_incompatible_msrv(input),
     };
 
-    Ok(allow_incompatible_msrv(output))
+    match msrv::get() {
+        Some(msrv) if msrv >= implied => return input,
+        Some(_) | None => {}
+    }
+
+    TokenStream::from_iter(
+        // #[clippy::msrv = #implied]
+        vec![
+            TokenTree::Punct(Punct::new('#', Spacing::Alone)),
+            TokenTree::Group(Group::new(
+                Delimiter::Bracket,
+                TokenStream::from_iter(vec![
+                    TokenTree::Ident(Ident::new("clippy", Span::call_site())),
+                    TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+                    TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+                    TokenTree::Ident(Ident::new("msrv", Span::call_site())),
+                    TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+                    TokenTree::Literal(Literal::string(&implied.to_string())),
+                ]),
+            )),
+        ]
+        .into_iter()
+        .chain(input),
+    )
 }
 
 fn allow_incompatible_msrv(input: TokenStream) -> TokenStream {
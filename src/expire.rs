@@ -0,0 +1,73 @@
+use crate::bound::{self, Bound};
+use crate::error::{Error, Result};
+use crate::iter::{self, Iter};
+use crate::token;
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+
+pub struct Args {
+    pub bound: Bound,
+    pub span: Span,
+    // Written as `compiler(...)`, to check the compiler building the crate
+    // rather than the crate's rust-version.
+    pub compiler: bool,
+    pub message: Option<Literal>,
+}
+
+pub fn parse(input: TokenStream) -> Result<Args> {
+    let paren = Group::new(Delimiter::Parenthesis, input);
+    let ref mut input = iter::new(paren.stream());
+
+    let compiler = match input.peek() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "compiler" => Some(ident.clone()),
+        _ => None,
+    };
+    let (bound, span) = if let Some(ref introducer) = compiler {
+        input.next();
+        let paren = token::parse_paren(introducer, input)?;
+        let ref mut inner = iter::new(paren.stream());
+        let span = inner.peek().map_or_else(|| paren.span(), TokenTree::span);
+        let bound = bound::parse(paren.clone(), inner)?;
+        token::parse_end(inner)?;
+        (bound, span)
+    } else {
+        let span = input.peek().map_or_else(Span::call_site, TokenTree::span);
+        let bound = bound::parse(paren, input)?;
+        (bound, span)
+    };
+
+    match bound {
+        Bound::Nightly(date) if compiler.is_none() => {
+            let msg = format!(
+                "a nightly date can only be checked against the compiler building the crate, as in compiler({})",
+                date,
+            );
+            return Err(Error::new(span, msg));
+        }
+        Bound::Nightly(_) | Bound::Stable(_) => {}
+    }
+
+    let mut message = None;
+    if token::parse_optional_punct(input, ',').is_some() && input.peek().is_some() {
+        message = Some(parse_message(input)?);
+        token::parse_optional_punct(input, ',');
+    }
+    token::parse_end(input)?;
+
+    Ok(Args {
+        bound,
+        span,
+        compiler: compiler.is_some(),
+        message,
+    })
+}
+
+// Kept as a literal, which rustc splices into the error with concat!.
+fn parse_message(iter: Iter) -> Result<Literal> {
+    let literal = token::parse_literal(iter)?;
+    let repr = literal.to_string();
+    if repr.starts_with('"') || repr.starts_with("r\"") || repr.starts_with("r#") {
+        Ok(literal)
+    } else {
+        Err(Error::new(literal.span(), "expected string literal"))
+    }
+}
@@ -1,73 +1,386 @@
 use crate::bound::{self, Bound};
-use crate::date::{self, Date};
+use crate::date::Date;
 use crate::error::{Error, Result};
 use crate::iter::{self, Iter};
 use crate::release::{self, Release};
-use crate::token;
 use crate::version::{Channel, Version};
-use proc_macro::{Ident, Span, TokenTree};
+use crate::{suggest, token};
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::iter::FromIterator;
 
 pub enum Expr {
     Stable,
     Beta,
+    BetaRelease(Release, Option<u16>),
     Nightly,
+    NightlyRelease(Release),
     Date(Date),
     Since(Bound),
     Before(Bound),
+    Between(Bound, Bound),
+    BetweenInclusive(Bound, Bound),
     Release(Release),
     Not(Box<Expr>),
     Any(Vec<Expr>),
     All(Vec<Expr>),
+    Cfg(TokenStream),
+}
+
+// What is left of an expression after evaluating its version selectors: either
+// a known value, or a cfg predicate that only rustc can evaluate.
+pub enum Residual {
+    Bool(bool),
+    Cfg(TokenStream),
 }
 
 impl Expr {
-    pub fn eval(&self, rustc: Version) -> bool {
+    pub fn eval(&self, rustc: Version) -> Residual {
         use self::Expr::*;
 
-        match self {
+        Residual::Bool(match self {
             Stable => rustc.channel == Channel::Stable,
-            Beta => rustc.channel == Channel::Beta,
+            Beta => match rustc.channel {
+                Channel::Beta(_) => true,
+                Channel::Stable | Channel::Nightly(_) | Channel::Dev => false,
+            },
+            BetaRelease(release, number) => match rustc.channel {
+                Channel::Beta(beta) => {
+                    rustc.major == release.major
+                        && rustc.minor == release.minor
+                        && release.patch.map_or(true, |patch| rustc.patch == patch)
+                        && number.map_or(true, |number| beta == Some(number))
+                }
+                Channel::Stable | Channel::Nightly(_) | Channel::Dev => false,
+            },
             Nightly => match rustc.channel {
                 Channel::Nightly(_) | Channel::Dev => true,
-                Channel::Stable | Channel::Beta => false,
+                Channel::Stable | Channel::Beta(_) => false,
+            },
+            NightlyRelease(release) => match rustc.channel {
+                Channel::Nightly(_) | Channel::Dev => {
+                    rustc.major == release.major
+                        && rustc.minor == release.minor
+                        && release.patch.map_or(true, |patch| rustc.patch == patch)
+                }
+                Channel::Stable | Channel::Beta(_) => false,
             },
             Date(date) => match rustc.channel {
                 Channel::Nightly(rustc) => rustc == *date,
-                Channel::Stable | Channel::Beta | Channel::Dev => false,
+                Channel::Stable | Channel::Beta(_) | Channel::Dev => false,
             },
             Since(bound) => rustc >= *bound,
             Before(bound) => rustc < *bound,
+            Between(lower, upper) => rustc >= *lower && rustc < *upper,
+            BetweenInclusive(lower, upper) => rustc >= *lower && rustc <= *upper,
             Release(release) => {
                 rustc.channel == Channel::Stable
+                    && rustc.major == release.major
                     && rustc.minor == release.minor
                     && release.patch.map_or(true, |patch| rustc.patch == patch)
             }
-            Not(expr) => !expr.eval(rustc),
-            Any(exprs) => exprs.iter().any(|e| e.eval(rustc)),
-            All(exprs) => exprs.iter().all(|e| e.eval(rustc)),
+            Not(expr) => return expr.eval(rustc).not(),
+            Any(exprs) => return Residual::any(exprs.iter().map(|e| e.eval(rustc))),
+            All(exprs) => return Residual::all(exprs.iter().map(|e| e.eval(rustc))),
+            Cfg(predicate) => return Residual::Cfg(predicate.clone()),
+        })
+    }
+
+    // The oldest release that the compiler must be at least for the expression
+    // to be true, if there is one. Selectors like beta(1.80) and nightly(1.80)
+    // do not count, since those compilers predate the 1.80 stable release.
+    pub fn implied_msrv(&self) -> Option<Release> {
+        use self::Expr::*;
+
+        match self {
+            Since(Bound::Stable(release))
+            | Between(Bound::Stable(release), _)
+            | BetweenInclusive(Bound::Stable(release), _)
+            | Release(release) => Some(*release),
+            Not(expr) => match **expr {
+                Before(Bound::Stable(release)) => Some(release),
+                _ => None,
+            },
+            Any(exprs) => exprs
+                .iter()
+                .map(Expr::implied_msrv)
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .min(),
+            All(exprs) => exprs.iter().filter_map(Expr::implied_msrv).max(),
+            Cfg(_)
+            | Stable
+            | Beta
+            | BetaRelease(..)
+            | Nightly
+            | NightlyRelease(_)
+            | Date(_)
+            | Since(Bound::Nightly(_))
+            | Before(_)
+            | Between(Bound::Nightly(_), _)
+            | BetweenInclusive(Bound::Nightly(_), _) => None,
+        }
+    }
+
+    // Whether the expression has the same value on every compiler whose
+    // version is at least `msrv`, and if so, which value. Channels and dates
+    // are not determined by the release number, so selectors of those are
+    // considered to vary.
+    pub fn eval_msrv(&self, msrv: Release) -> Option<bool> {
+        use self::Expr::*;
+
+        let floor = Version::from_release(msrv);
+
+        match self {
+            Stable | Beta | Nightly | Date(_) | Cfg(_) => None,
+            Since(bound) => since_msrv(floor, *bound),
+            Before(bound) => since_msrv(floor, *bound).map(|value| !value),
+            Between(lower, upper) => and(
+                since_msrv(floor, *lower),
+                since_msrv(floor, *upper).map(|value| !value),
+            ),
+            BetweenInclusive(lower, upper) => and(
+                since_msrv(floor, *lower),
+                match upper {
+                    Bound::Stable(_) if floor > *upper => Some(false),
+                    Bound::Stable(_) | Bound::Nightly(_) => None,
+                },
+            ),
+            Release(release) | BetaRelease(release, _) | NightlyRelease(release) => {
+                if floor > Bound::Stable(*release) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Not(expr) => expr.eval_msrv(msrv).map(|value| !value),
+            Any(exprs) => exprs
+                .iter()
+                .map(|e| e.eval_msrv(msrv).map(|value| !value))
+                .fold(Some(true), and)
+                .map(|value| !value),
+            All(exprs) => exprs
+                .iter()
+                .map(|e| e.eval_msrv(msrv))
+                .fold(Some(true), and),
         }
     }
 }
 
+// Every compiler at or above `floor` is at or above a release bound; nothing
+// can be said about a date bound.
+fn since_msrv(floor: Version, bound: Bound) -> Option<bool> {
+    match bound {
+        Bound::Stable(_) if floor >= bound => Some(true),
+        Bound::Stable(_) | Bound::Nightly(_) => None,
+    }
+}
+
+fn and(lhs: Option<bool>, rhs: Option<bool>) -> Option<bool> {
+    match (lhs, rhs) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        (Some(true), None) | (None, Some(true)) | (None, None) => None,
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        use self::Expr::*;
+
+        match self {
+            Stable => formatter.write_str("stable"),
+            Beta => formatter.write_str("beta"),
+            BetaRelease(release, None) => write!(formatter, "beta({})", release),
+            BetaRelease(release, Some(number)) => {
+                write!(formatter, "beta({}, {})", release, number)
+            }
+            Nightly => formatter.write_str("nightly"),
+            NightlyRelease(release) => write!(formatter, "nightly({})", release),
+            Date(date) => write!(formatter, "nightly({})", date),
+            Since(bound) => write!(formatter, "since({})", bound),
+            Before(bound) => write!(formatter, "before({})", bound),
+            Between(lower, upper) => write!(formatter, "between({}, {})", lower, upper),
+            BetweenInclusive(lower, upper) => {
+                write!(formatter, "between({}, ={})", lower, upper)
+            }
+            Release(release) => write!(formatter, "stable({})", release),
+            Not(expr) => write!(formatter, "not({})", expr),
+            Any(exprs) => write_list(formatter, "any", exprs),
+            All(exprs) => write_list(formatter, "all", exprs),
+            Cfg(predicate) => write!(formatter, "cfg({})", predicate),
+        }
+    }
+}
+
+impl Residual {
+    fn not(self) -> Self {
+        match self {
+            Residual::Bool(value) => Residual::Bool(!value),
+            Residual::Cfg(predicate) => Residual::Cfg(compound("not", vec![predicate])),
+        }
+    }
+
+    fn any(residuals: impl Iterator<Item = Residual>) -> Self {
+        let mut predicates = Vec::new();
+        for residual in residuals {
+            match residual {
+                Residual::Bool(true) => return Residual::Bool(true),
+                Residual::Bool(false) => {}
+                Residual::Cfg(predicate) => predicates.push(predicate),
+            }
+        }
+        combine("any", predicates, false)
+    }
+
+    fn all(residuals: impl Iterator<Item = Residual>) -> Self {
+        let mut predicates = Vec::new();
+        for residual in residuals {
+            match residual {
+                Residual::Bool(false) => return Residual::Bool(false),
+                Residual::Bool(true) => {}
+                Residual::Cfg(predicate) => predicates.push(predicate),
+            }
+        }
+        combine("all", predicates, true)
+    }
+}
+
+fn combine(name: &str, mut predicates: Vec<TokenStream>, empty: bool) -> Residual {
+    match predicates.len() {
+        0 => Residual::Bool(empty),
+        1 => Residual::Cfg(predicates.pop().unwrap()),
+        _ => Residual::Cfg(compound(name, predicates)),
+    }
+}
+
+// #name(#predicates,*)
+pub fn compound(name: &str, predicates: Vec<TokenStream>) -> TokenStream {
+    let mut args = TokenStream::new();
+    for predicate in predicates {
+        if !args.is_empty() {
+            args.extend(std::iter::once(TokenTree::Punct(Punct::new(
+                ',',
+                Spacing::Alone,
+            ))));
+        }
+        args.extend(predicate);
+    }
+    TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new(name, Span::call_site())),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, args)),
+    ])
+}
+
+fn write_list(formatter: &mut fmt::Formatter, name: &str, exprs: &[Expr]) -> fmt::Result {
+    write!(formatter, "{}(", name)?;
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            formatter.write_str(", ")?;
+        }
+        Display::fmt(expr, formatter)?;
+    }
+    formatter.write_str(")")
+}
+
 pub fn parse(iter: Iter) -> Result<Expr> {
     match &iter.next() {
         Some(TokenTree::Ident(i)) if i.to_string() == "stable" => parse_stable(iter),
-        Some(TokenTree::Ident(i)) if i.to_string() == "beta" => Ok(Expr::Beta),
+        Some(TokenTree::Ident(i)) if i.to_string() == "beta" => parse_beta(iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "nightly" => parse_nightly(iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "since" => parse_since(i, iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "before" => parse_before(i, iter),
+        Some(TokenTree::Ident(i)) if i.to_string() == "between" => parse_between(i, iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "not" => parse_not(i, iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "any" => parse_any(i, iter),
         Some(TokenTree::Ident(i)) if i.to_string() == "all" => parse_all(i, iter),
-        unexpected => {
-            let span = unexpected
-                .as_ref()
-                .map_or_else(Span::call_site, TokenTree::span);
-            Err(Error::new(span, "expected one of `stable`, `beta`, `nightly`, `since`, `before`, `not`, `any`, `all`"))
+        Some(TokenTree::Ident(i)) if i.to_string() == "cfg" => parse_cfg(i, iter),
+        unexpected => Err(unexpected_selector(unexpected.as_ref(), iter)),
+    }
+}
+
+const SELECTORS: &[&str] = &[
+    "stable", "beta", "nightly", "since", "before", "between", "not", "any", "all", "cfg",
+];
+
+fn unexpected_selector(unexpected: Option<&TokenTree>, iter: Iter) -> Error {
+    let span = unexpected.map_or_else(Span::call_site, TokenTree::span);
+
+    match unexpected {
+        Some(TokenTree::Ident(ident)) => {
+            let name = ident.to_string();
+            if name == "rust_version" || name == "msrv" {
+                let msg = "to compare against the crate's rust-version, use `since(msrv)`";
+                return Error::new(span, msg);
+            }
+            if name == "version" && token::parse_optional_punct(iter, '=').is_some() {
+                if let Some(TokenTree::Literal(literal)) = iter.peek() {
+                    let version = literal.to_string().replace('"', "");
+                    let msg = format!(
+                        "to match compilers of at least this version, use `since({})`",
+                        version
+                    );
+                    return Error::new(span, msg);
+                }
+            }
+            if let Some(suggestion) = suggest::similar(&name, SELECTORS) {
+                let msg = format!(
+                    "unknown selector `{}`, did you mean `{}`?",
+                    name, suggestion
+                );
+                return Error::new(span, msg);
+            }
         }
+        Some(TokenTree::Punct(punct)) if is_comparison(punct) => {
+            return comparison(punct, iter);
+        }
+        _ => {}
+    }
+
+    Error::new(span, "expected one of `stable`, `beta`, `nightly`, `since`, `before`, `between`, `not`, `any`, `all`, `cfg`")
+}
+
+pub fn is_comparison(punct: &Punct) -> bool {
+    match punct.as_char() {
+        '<' | '>' | '=' => true,
+        _ => false,
     }
 }
 
+// An error for a comparison like `>= 1.80`, suggesting the selector to write
+// instead.
+pub fn comparison(first: &Punct, iter: Iter) -> Error {
+    let mut operator = first.as_char().to_string();
+    let mut end = first.span();
+    while let Some(TokenTree::Punct(punct)) = iter.peek() {
+        if !is_comparison(punct) {
+            break;
+        }
+        operator.push(punct.as_char());
+        end = punct.span();
+        iter.next();
+    }
+
+    let version = match iter.peek() {
+        Some(TokenTree::Literal(literal)) => {
+            end = literal.span();
+            literal.to_string()
+        }
+        _ => "1.80".to_owned(),
+    };
+
+    let msg = match operator.as_str() {
+        ">=" => format!("use `since({})` instead of `>= {}`", version, version),
+        "<" => format!("use `before({})` instead of `< {}`", version, version),
+        _ => format!(
+            "comparison operators are not supported; use `since({0})` for `>= {0}`, or `before({0})` for `< {0}`",
+            version,
+        ),
+    };
+    Error::new2(first.span(), end, msg)
+}
+
 fn parse_nightly(iter: Iter) -> Result<Expr> {
     let paren = match token::parse_optional_paren(iter) {
         Some(group) => group,
@@ -75,11 +388,14 @@ fn parse_nightly(iter: Iter) -> Result<Expr> {
     };
 
     let ref mut inner = iter::new(paren.stream());
-    let date = date::parse(paren, inner)?;
+    let expr = match bound::parse(paren, inner)? {
+        Bound::Nightly(date) => Expr::Date(date),
+        Bound::Stable(release) => Expr::NightlyRelease(release),
+    };
     token::parse_optional_punct(inner, ',');
     token::parse_end(inner)?;
 
-    Ok(Expr::Date(date))
+    Ok(expr)
 }
 
 fn parse_stable(iter: Iter) -> Result<Expr> {
@@ -96,6 +412,38 @@ fn parse_stable(iter: Iter) -> Result<Expr> {
     Ok(Expr::Release(release))
 }
 
+fn parse_beta(iter: Iter) -> Result<Expr> {
+    let paren = match token::parse_optional_paren(iter) {
+        Some(group) => group,
+        None => return Ok(Expr::Beta),
+    };
+
+    let ref mut inner = iter::new(paren.stream());
+    let release = release::parse(paren, inner)?;
+    let mut number = None;
+    if token::parse_optional_punct(inner, ',').is_some() && inner.peek().is_some() {
+        number = Some(parse_beta_number(inner)?);
+        token::parse_optional_punct(inner, ',');
+    }
+    token::parse_end(inner)?;
+
+    Ok(Expr::BetaRelease(release, number))
+}
+
+fn parse_beta_number(iter: Iter) -> Result<u16> {
+    match iter.next() {
+        Some(TokenTree::Literal(ref literal)) if literal.to_string().parse::<u16>().is_ok() => {
+            Ok(literal.to_string().parse().unwrap())
+        }
+        unexpected => {
+            let span = unexpected
+                .as_ref()
+                .map_or_else(Span::call_site, TokenTree::span);
+            Err(Error::new(span, "expected beta number, like 3"))
+        }
+    }
+}
+
 fn parse_since(introducer: &Ident, iter: Iter) -> Result<Expr> {
     let paren = token::parse_paren(introducer, iter)?;
 
@@ -118,6 +466,35 @@ fn parse_before(introducer: &Ident, iter: Iter) -> Result<Expr> {
     Ok(Expr::Before(bound))
 }
 
+fn parse_between(introducer: &Ident, iter: Iter) -> Result<Expr> {
+    let paren = token::parse_paren(introducer, iter)?;
+
+    let ref mut inner = iter::new(paren.stream());
+    let lower = bound::parse(paren.clone(), inner)?;
+    token::parse_punct(inner, ',')?;
+    let inclusive = token::parse_optional_punct(inner, '=').is_some();
+    let upper = bound::parse(paren.clone(), inner)?;
+    token::parse_optional_punct(inner, ',');
+    token::parse_end(inner)?;
+
+    match bound::compare(lower, upper) {
+        None => Err(Error::group(
+            paren,
+            "expected both bounds to be release numbers or both to be nightly dates",
+        )),
+        Some(Ordering::Less) => Ok(if inclusive {
+            Expr::BetweenInclusive(lower, upper)
+        } else {
+            Expr::Between(lower, upper)
+        }),
+        Some(Ordering::Equal) if inclusive => Ok(Expr::BetweenInclusive(lower, upper)),
+        Some(Ordering::Equal) | Some(Ordering::Greater) => Err(Error::group(
+            paren,
+            "expected lower bound to be below upper bound",
+        )),
+    }
+}
+
 fn parse_not(introducer: &Ident, iter: Iter) -> Result<Expr> {
     let paren = token::parse_paren(introducer, iter)?;
 
@@ -147,6 +524,16 @@ fn parse_all(introducer: &Ident, iter: Iter) -> Result<Expr> {
     Ok(Expr::All(exprs.into_iter().collect()))
 }
 
+fn parse_cfg(introducer: &Ident, iter: Iter) -> Result<Expr> {
+    let paren = token::parse_paren(introducer, iter)?;
+
+    if paren.stream().is_empty() {
+        return Err(Error::new(paren.span(), "expected cfg predicate"));
+    }
+
+    Ok(Expr::Cfg(paren.stream()))
+}
+
 fn parse_comma_separated(iter: Iter) -> Result<Vec<Expr>> {
     let mut exprs = Vec::new();
 
@@ -36,6 +36,13 @@
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::beta(1.85)]</code></b>
+//!   —<br>
+//!   True on any beta of the specified release. A beta number may follow, as
+//!   in <i>#[rustversion::beta(1.85, 3)]</i>, to match only 1.85.0-beta.3.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
 //!   <b><code style="display:inline">#[rustversion::nightly]</code></b>
 //!   —<br>
 //!   True on any nightly compiler or dev build.
@@ -48,6 +55,12 @@
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::nightly(1.86)]</code></b>
+//!   —<br>
+//!   True on any nightly compiler or dev build of the specified release.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
 //!   <b><code style="display:inline">#[rustversion::since(1.34)]</code></b>
 //!   —<br>
 //!   True on that stable release and any later compiler, including beta and
@@ -57,7 +70,8 @@
 //! - <p style="margin-left:50px;text-indent:-50px">
 //!   <b><code style="display:inline">#[rustversion::since(2025-01-01)]</code></b>
 //!   —<br>
-//!   True on that nightly and all newer ones.
+//!   True on that nightly and all newer ones, as well as on any stable or beta
+//!   compiler built from a commit on or after that date.
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
@@ -67,6 +81,22 @@
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::between(1.70, 1.80)]</code></b>
+//!   —<br>
+//!   True from the first bound up to but not including the second; same as
+//!   <i>all(since(1.70), before(1.80))</i>. Writing <i>between(1.70, =1.80)</i>
+//!   includes the second bound as well. The bounds may also be nightly dates.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::since(msrv)]</code></b>
+//!   —<br>
+//!   Anywhere a release number is accepted as a bound, <i>msrv</i> stands for
+//!   the <code style="display:inline">rust-version</code> declared in the
+//!   crate's Cargo.toml.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
 //!   <b><code style="display:inline">#[rustversion::not(</code></b><i>selector</i><b><code style="display:inline">)]</code></b>
 //!   —<br>
 //!   Negative of any selector; for example <i>#[rustversion::not(nightly)]</i>.
@@ -87,10 +117,31 @@
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::all(since(1.70), cfg(</code></b><i>predicate</i><b><code style="display:inline">))]</code></b>
+//!   —<br>
+//!   Inside <i>not</i>, <i>any</i> and <i>all</i>, an ordinary cfg predicate
+//!   can be combined with the version selectors; whatever the compiler version
+//!   does not decide is left to <code style="display:inline">#[cfg]</code>.
+//!   Not supported in <i>select!</i> or <i>attr_match</i>, whose arm is
+//!   chosen before cfg is evaluated.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
 //!   <b><code style="display:inline">#[rustversion::attr(</code></b><i>selector</i><b><code style="display:inline">, </code></b><i>attribute</i><b><code style="display:inline">)]</code></b>
 //!   —<br>
 //!   For conditional inclusion of attributes; analogous to
-//!   <code style="display:inline">cfg_attr</code>.
+//!   <code style="display:inline">cfg_attr</code>. An alternative for
+//!   compilers on which the selector is false may follow as <i>else =
+//!   attribute</i>; for example
+//!   <i>#[rustversion::attr(since(1.80), repr(u8), else = repr(u16))]</i>.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::attr_match(</code></b><i>selector</i><b><code style="display:inline"> => </code></b><i>attribute</i><b><code style="display:inline">, _ => </code></b><i>attribute</i><b><code style="display:inline">)]</code></b>
+//!   —<br>
+//!   Applies the attribute of the first arm whose selector is true, where
+//!   <i>_</i> is always true; for attributes that changed more than once, like
+//!   a lint that was renamed twice.
 //!   </p>
 //!
 //! - <p style="margin-left:50px;text-indent:-50px">
@@ -100,6 +151,61 @@
 //!   <i>if rustversion::cfg!(any(stable, beta)) { ... }</i>.
 //!   </p>
 //!
+//! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">rustversion::select! {</code></b><i>selector</i><b><code style="display:inline"> => { ... }, _ => { ... } }</code></b>
+//!   —<br>
+//!   Expands to the contents of the first arm whose selector is true, where
+//!   <i>_</i> is always true. Arms that are not selected are never compiled, so
+//!   they may refer to APIs that do not exist on the current compiler. An
+//!   expression arm made of statements needs a block of its own, as in
+//!   <i>_ => {{ let a = 1; a + 1 }}</i>.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::process]</code></b>
+//!   —<br>
+//!   Applies helper attributes <i>#[rustversion(selector)]</i> and
+//!   <i>#[rustversion(attr(selector, attribute))]</i> found on the fields,
+//!   variants, match arms and statements inside the item, where attribute
+//!   macros are not allowed. The element following the helper is kept only if
+//!   the selector is true.
+//!   </p>
+//!
+//! - <p style="margin-left:50px;text-indent:-50px">
+//!   <b><code style="display:inline">#[rustversion::remove_after(1.90, "</code></b><i>message</i><b><code style="display:inline">")]</code></b>
+//!   —<br>
+//!   Leaves the item unchanged, but fails the build once the crate's
+//!   rust-version reaches the specified release, as a reminder to delete
+//!   polyfills that are no longer needed. Writing
+//!   <i>remove_after(compiler(1.90))</i> checks the compiler building the
+//!   crate instead, which also accepts a nightly date. That fails the build
+//!   for anyone who compiles the crate with a newer compiler, so it is best
+//!   kept out of published libraries.
+//!   </p>
+//!
+//! When a crate declares a `rust-version`, attributes whose condition is the
+//! same on every compiler allowed by that rust-version, such as
+//! <i>#[rustversion::before(1.60)]</i> in a crate with rust-version 1.60,
+//! produce a deprecation warning so they can be cleaned up.
+//!
+//! <br>
+//!
+//! # Version information
+//!
+//! The detected compiler version is also available as literals, for example
+//! for logging or embedding in crash reports.
+//!
+//! - <code style="display:inline">rustversion::version!()</code> — string
+//!   like <i>"1.85.0"</i> or <i>"1.86.0-nightly"</i>.
+//! - <code style="display:inline">rustversion::major!()</code>,
+//!   <code style="display:inline">minor!()</code>,
+//!   <code style="display:inline">patch!()</code> — integers.
+//! - <code style="display:inline">rustversion::channel!()</code> — one of
+//!   <i>"stable"</i>, <i>"beta"</i>, <i>"nightly"</i>, <i>"dev"</i>.
+//! - <code style="display:inline">rustversion::nightly_date!()</code> —
+//!   string like <i>"2025-01-01"</i>; a compile error on a compiler other than
+//!   a dated nightly.
+//!
 //! <br>
 //!
 //! # Use cases
@@ -150,6 +256,59 @@
 //! }
 //! ```
 //!
+//! On an inherent impl block, `const` is inserted on every method that is not
+//! already const, except those marked `#[rustversion::not_const]`.
+//!
+//! ```
+//! # struct Counter(u64);
+//! #
+//! #[rustversion::attr(since(1.83), const)]
+//! impl Counter {
+//!     fn get(&self) -> u64 {
+//!         self.0
+//!     }
+//!
+//!     fn get_mut(&mut self) -> &mut u64 {
+//!         &mut self.0
+//!     }
+//!
+//!     #[rustversion::not_const]
+//!     fn to_string(&self) -> String {
+//!         self.0.to_string()
+//!     }
+//! }
+//! ```
+//!
+//! Likewise `async`, `unsafe` and `extern "C"` are inserted in their place
+//! among the qualifiers of a fn. `unsafe` also marks an extern block as
+//! `unsafe extern` only on compilers that accept it, and an attribute written
+//! as `unsafe(...)` is applied in its unsafe form on compilers that support
+//! unsafe attributes and as the plain attribute on older ones.
+//!
+//! ```
+//! #[rustversion::attr(since(1.82), unsafe)]
+//! extern "C" {
+//!     fn abs(i: i32) -> i32;
+//! }
+//!
+//! #[rustversion::attr(since(1.82), unsafe(no_mangle))]
+//! pub extern "C" fn callback() {}
+//! #
+//! # fn main() {}
+//! ```
+//!
+//! In the other direction, `!const`, `!async`, `!unsafe` and `!extern` remove
+//! a qualifier that the item has. This keeps a fn written as `const fn` while
+//! building it as an ordinary fn on compilers that cannot evaluate its body at
+//! compile time.
+//!
+//! ```
+//! #[rustversion::attr(before(1.83), !const)]
+//! pub const fn first_mut(slice: &mut [u8]) -> Option<&mut u8> {
+//!     slice.first_mut()
+//! }
+//! ```
+//!
 //! Emitting Cargo cfg directives from a build script. Note that this requires
 //! listing `rustversion` under `[build-dependencies]` in Cargo.toml, not
 //! `[dependencies]`.
@@ -174,6 +333,68 @@
 //! ```
 //!
 //! <br>
+//!
+//! # Testing other compilers
+//!
+//! Setting the `RUSTVERSION_OVERRIDE` environment variable at build time makes
+//! rustversion evaluate every selector against the given version instead of
+//! the version of the compiler actually in use. This makes it possible to
+//! exercise each side of a version gate without installing every toolchain.
+//! The value is anything that could appear after `rustc` in the output of
+//! `rustc --version`, such as `1.70.0`, `1.86.0-beta.4`, or
+//! `1.87.0-nightly (abc 2025-03-01)`.
+//!
+//! # Documenting version requirements
+//!
+//! Passing `--cfg rustversion_doc_notes` to rustdoc when documenting a crate
+//! makes every item of that crate kept by a rustversion selector attribute
+//! carry a note in its documentation, such as "Available on **Rust 1.80+**
+//! only". On docs.rs this is done through `rustdoc-args`:
+//!
+//! ```toml
+//! [package.metadata.docs.rs]
+//! rustdoc-args = ["--cfg", "rustversion_doc_notes"]
+//! ```
+//!
+//! Locally, `cargo rustdoc -- --cfg rustversion_doc_notes` does the same for
+//! one crate. The notes are emitted as
+//! <i>#[cfg_attr(doc, cfg_attr(rustversion_doc_notes, doc = "..."))]</i>, so a
+//! crate that is documented should declare the cfg to avoid an
+//! `unexpected_cfgs` warning from rustdoc:
+//!
+//! ```toml
+//! [lints.rust]
+//! unexpected_cfgs = { level = "warn", check-cfg = ["cfg(rustversion_doc_notes)"] }
+//! ```
+//!
+//! Documentation mode instead keeps every item gated by a rustversion selector
+//! attribute in the documentation of every crate, whichever compiler builds
+//! it, so that APIs that exist only for older or newer compilers do not
+//! disappear. Items whose condition is false for the documenting compiler are
+//! kept under <i>#[cfg(doc)]</i>, and every gated item carries the note of its
+//! requirement. It is enabled by setting the `RUSTVERSION_DOCS` environment
+//! variable, or by `--cfg rustversion_docs` in the `RUSTDOCFLAGS` environment
+//! variable. Flags given through `cargo rustdoc --` or `build.rustdocflags`
+//! are not visible to rustversion's build script and do not enable it.
+//!
+//! Keeping every item means two alternatives with the same name, such as one
+//! under <i>since(1.80)</i> and one under <i>before(1.80)</i>, would both be
+//! documented and clash. Alternatives like these must be written with
+//! <i>select!</i>, which in documentation mode as always expands to the one
+//! arm selected by the compiler:
+//!
+//! ```
+//! rustversion::select! {
+//!     since(1.80) => {
+//!         pub fn duration() -> u8 { 1 }
+//!     },
+//!     _ => {
+//!         pub fn duration() -> u8 { 0 }
+//!     },
+//! }
+//! ```
+//!
+//! <br>
 
 #![doc(html_root_url = "https://docs.rs/rustversion/1.0.22")]
 #![allow(
@@ -201,16 +422,22 @@ mod constfn;
 mod date;
 mod error;
 mod expand;
+mod expire;
 mod expr;
 mod iter;
+mod msrv;
+mod process;
 mod release;
+mod select;
+mod suggest;
 mod time;
 mod token;
 mod version;
 
 use crate::error::Error;
-use crate::version::Version;
-use proc_macro::TokenStream;
+use crate::expr::Residual;
+use crate::version::{Channel, Version};
+use proc_macro::{Literal, Span, TokenStream, TokenTree};
 
 #[cfg(not(host_os = "windows"))]
 const RUSTVERSION: Version = include!(concat!(env!("OUT_DIR"), "/version.expr"));
@@ -243,6 +470,11 @@ pub fn before(args: TokenStream, input: TokenStream) -> TokenStream {
     expand::cfg("before", args, input)
 }
 
+#[proc_macro_attribute]
+pub fn between(args: TokenStream, input: TokenStream) -> TokenStream {
+    expand::cfg("between", args, input)
+}
+
 #[proc_macro_attribute]
 pub fn not(args: TokenStream, input: TokenStream) -> TokenStream {
     expand::cfg("not", args, input)
@@ -265,17 +497,122 @@ pub fn attr(args: TokenStream, input: TokenStream) -> TokenStream {
         .unwrap_or_else(Error::into_compile_error)
 }
 
+#[proc_macro_attribute]
+pub fn attr_match(args: TokenStream, input: TokenStream) -> TokenStream {
+    attr::parse_match(args)
+        .and_then(|arms| expand::attr_match(arms, input))
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+#[proc_macro_attribute]
+pub fn not_const(args: TokenStream, input: TokenStream) -> TokenStream {
+    // Only meaningful inside an impl block given #[rustversion::attr(..., const)],
+    // which removes it. Anywhere else it has no effect.
+    let ref mut args = iter::new(args);
+    match token::parse_end(args) {
+        Ok(()) => input,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn process(args: TokenStream, input: TokenStream) -> TokenStream {
+    process::process(args, input).unwrap_or_else(Error::into_compile_error)
+}
+
+#[proc_macro_attribute]
+pub fn remove_after(args: TokenStream, input: TokenStream) -> TokenStream {
+    match expire::parse(args) {
+        Ok(args) => expand::remove_after(args, input),
+        Err(err) => err.into_compile_error(),
+    }
+}
+
 #[cfg(not(cfg_macro_not_allowed))]
 #[proc_macro]
 pub fn cfg(input: TokenStream) -> TokenStream {
-    use proc_macro::{Ident, Span, TokenTree};
+    use proc_macro::{Delimiter, Group, Ident, Punct, Spacing};
+    use std::iter::FromIterator;
     (|| {
         let ref mut args = iter::new(input);
         let expr = expr::parse(args)?;
         token::parse_end(args)?;
-        let boolean = expr.eval(RUSTVERSION);
-        let ident = Ident::new(&boolean.to_string(), Span::call_site());
-        Ok(TokenStream::from(TokenTree::Ident(ident)))
+        match expr.eval(RUSTVERSION) {
+            Residual::Bool(boolean) => {
+                let ident = Ident::new(&boolean.to_string(), Span::call_site());
+                Ok(TokenStream::from(TokenTree::Ident(ident)))
+            }
+            Residual::Cfg(predicate) => {
+                // ::core::cfg!(#predicate), which stays the builtin even where
+                // rustversion::cfg is imported as `cfg`.
+                Ok(TokenStream::from_iter(vec![
+                    TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+                    TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+                    TokenTree::Ident(Ident::new("core", Span::call_site())),
+                    TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+                    TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+                    TokenTree::Ident(Ident::new("cfg", Span::call_site())),
+                    TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+                    TokenTree::Group(Group::new(Delimiter::Parenthesis, predicate)),
+                ]))
+            }
+        }
+    })()
+    .unwrap_or_else(Error::into_compile_error)
+}
+
+#[proc_macro]
+pub fn select(input: TokenStream) -> TokenStream {
+    select::parse(input)
+        .and_then(expand::select)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+#[proc_macro]
+pub fn version(input: TokenStream) -> TokenStream {
+    literal(input, || Ok(Literal::string(&RUSTVERSION.to_string())))
+}
+
+#[proc_macro]
+pub fn major(input: TokenStream) -> TokenStream {
+    literal(input, || Ok(Literal::u16_unsuffixed(RUSTVERSION.major)))
+}
+
+#[proc_macro]
+pub fn minor(input: TokenStream) -> TokenStream {
+    literal(input, || Ok(Literal::u16_unsuffixed(RUSTVERSION.minor)))
+}
+
+#[proc_macro]
+pub fn patch(input: TokenStream) -> TokenStream {
+    literal(input, || Ok(Literal::u16_unsuffixed(RUSTVERSION.patch)))
+}
+
+#[proc_macro]
+pub fn channel(input: TokenStream) -> TokenStream {
+    literal(input, || Ok(Literal::string(RUSTVERSION.channel.name())))
+}
+
+#[proc_macro]
+pub fn nightly_date(input: TokenStream) -> TokenStream {
+    literal(input, || match RUSTVERSION.channel {
+        Channel::Nightly(date) => Ok(Literal::string(&date.to_string())),
+        Channel::Stable | Channel::Beta(_) | Channel::Dev => {
+            let msg = format!(
+                "nightly_date! requires a nightly compiler, but this is rustc {}",
+                RUSTVERSION,
+            );
+            Err(Error::new(Span::call_site(), msg))
+        }
+    })
+}
+
+fn literal(input: TokenStream, f: impl FnOnce() -> error::Result<Literal>) -> TokenStream {
+    (|| {
+        let ref mut args = iter::new(input);
+        token::parse_end(args)?;
+        let literal = f()?;
+        Ok(TokenStream::from(TokenTree::Literal(literal)))
     })()
     .unwrap_or_else(Error::into_compile_error)
 }
@@ -0,0 +1,9 @@
+use crate::release::{self, Release};
+use std::env;
+
+// The rust-version declared in the Cargo.toml of the crate being compiled,
+// which Cargo passes to rustc and therefore to proc macros.
+pub fn get() -> Option<Release> {
+    let rust_version = env::var("CARGO_PKG_RUST_VERSION").ok()?;
+    release::parse_str(&rust_version)
+}
@@ -0,0 +1,269 @@
+use crate::error::Result;
+use crate::expr::Residual;
+use crate::{attr, expand, expr, iter, token};
+use proc_macro::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+
+pub fn process(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    token::parse_end(&mut iter::new(args))?;
+    let tokens: Vec<TokenTree> = iter::new(input).collect();
+    process_tokens(&tokens)
+}
+
+// Finds #[rustversion(...)] helper attributes, including inside nested groups,
+// and applies each one to the element that follows it.
+fn process_tokens(tokens: &[TokenTree]) -> Result<TokenStream> {
+    let mut out = TokenStream::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let Some(helper) = helper(tokens, i) {
+            let end = element_end(tokens, i + 2);
+            let element = process_tokens(&tokens[i + 2..end])?;
+            out.extend(apply(helper, element)?);
+            i = end;
+            continue;
+        }
+
+        match tokens[i] {
+            TokenTree::Group(ref group) => {
+                let stream: Vec<TokenTree> = iter::new(group.stream()).collect();
+                let mut processed = Group::new(group.delimiter(), process_tokens(&stream)?);
+                processed.set_span(group.span());
+                out.extend(std::iter::once(TokenTree::Group(processed)));
+            }
+            ref token => out.extend(std::iter::once(token.clone())),
+        }
+        i += 1;
+    }
+
+    Ok(out)
+}
+
+// The arguments of #[rustversion(...)] if there is one at tokens[i].
+fn helper(tokens: &[TokenTree], i: usize) -> Option<TokenStream> {
+    match (tokens.get(i), tokens.get(i + 1)) {
+        (Some(TokenTree::Punct(pound)), Some(TokenTree::Group(attr)))
+            if pound.as_char() == '#' && attr.delimiter() == Delimiter::Bracket =>
+        {
+            let mut attr = attr.stream().into_iter();
+            match (attr.next(), attr.next(), attr.next()) {
+                (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(args)), None)
+                    if ident.to_string() == "rustversion"
+                        && args.delimiter() == Delimiter::Parenthesis =>
+                {
+                    Some(args.stream())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn apply(helper: TokenStream, element: TokenStream) -> Result<TokenStream> {
+    let ref mut input = iter::new(helper);
+
+    let introducer = match input.peek() {
+        Some(TokenTree::Ident(ident)) => match ident.to_string().as_str() {
+            "attr" | "attr_match" => Some(ident.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(introducer) = introducer {
+        input.next();
+        let args = token::parse_paren(&introducer, input)?.stream();
+        token::parse_end(input)?;
+        return if introducer.to_string() == "attr" {
+            let args = attr::parse(args)?;
+            expand::attr_output(&args.condition, args.then, args.otherwise, element)
+        } else {
+            expand::attr_match(attr::parse_match(args)?, element)
+        };
+    }
+
+    let expr = expr::parse(input)?;
+    token::parse_end(input)?;
+    Ok(match expr.eval(crate::RUSTVERSION) {
+        Residual::Bool(true) => element,
+        Residual::Bool(false) => TokenStream::new(),
+        Residual::Cfg(predicate) => expand::with_cfg(predicate, element),
+    })
+}
+
+// The element following a helper attribute is a field, variant, match arm or
+// statement. A field, variant or match arm extends up to and including the
+// next `,` that is not inside generic arguments or closure parameters. A
+// statement ends only at `;`, or at the end of a block that is not continued,
+// as in `if ... {} else {}`.
+fn element_end(tokens: &[TokenTree], start: usize) -> usize {
+    let statement = match statement_keyword(tokens, start) {
+        Some(keyword) => keyword,
+        None => return list_element_end(tokens, start),
+    };
+
+    let mut i = start;
+    while i < tokens.len() {
+        match tokens[i] {
+            TokenTree::Punct(ref punct) if punct.as_char() == ';' => return i + 1,
+            // A `let` statement may contain blocks anywhere in its expression.
+            TokenTree::Group(ref group)
+                if group.delimiter() == Delimiter::Brace
+                    && statement != "let"
+                    && !is_continued(tokens.get(i + 1)) =>
+            {
+                return i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    i
+}
+
+// The keyword that starts a `let` statement or an item, which none of the
+// other elements can start with.
+fn statement_keyword(tokens: &[TokenTree], start: usize) -> Option<String> {
+    let mut i = start;
+    // pub(crate) fn, but not a field like pub(crate) x: u8
+    if let Some(TokenTree::Ident(ident)) = tokens.get(i) {
+        if ident.to_string() == "pub" {
+            i += 1;
+            if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    i += 1;
+                }
+            }
+        }
+    }
+    match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) => {
+            let keyword = ident.to_string();
+            match keyword.as_str() {
+                "let" | "fn" | "const" | "static" | "struct" | "enum" | "union" | "trait"
+                | "impl" | "mod" | "use" | "type" | "unsafe" | "async" | "extern"
+                | "macro_rules" => Some(keyword),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn list_element_end(tokens: &[TokenTree], start: usize) -> usize {
+    let mut i = start;
+    // Angle bracket depth, so that the comma in `HashMap<K, V>` or in
+    // `max::<u8, u8>(...)` does not end the element.
+    let mut depth = 0;
+    // Whether this is past the `=` of a discriminant, the `=>` of a match arm
+    // or the `if` of a guard. Before that, the element is a type, a field
+    // name followed by a type, or a pattern.
+    let mut expression = false;
+    let mut arrow = None;
+    let mut closure_params = false;
+    // In case an angle bracket is never closed, the element ends at the first
+    // comma after all.
+    let mut first_comma = None;
+
+    while i < tokens.len() {
+        match tokens[i] {
+            TokenTree::Punct(ref punct) => match punct.as_char() {
+                ';' => return i + 1,
+                ',' if closure_params => {}
+                ',' if depth == 0 => return i + 1,
+                ',' => {
+                    first_comma = first_comma.or(Some(i));
+                }
+                '=' | '-'
+                    if punct.spacing() == Spacing::Joint && is_punct(tokens.get(i + 1), '>') =>
+                {
+                    if punct.as_char() == '=' {
+                        depth = 0;
+                        expression = true;
+                        arrow = Some(i + 2);
+                    }
+                    i += 2;
+                    continue;
+                }
+                '=' if depth == 0 => expression = true,
+                // In an expression, `<` is a comparison or a shift unless it
+                // opens the generic arguments of a turbofish.
+                '<' if !expression || depth > 0 || is_turbofish(tokens, i) => depth += 1,
+                '>' if depth > 0 => depth -= 1,
+                '|' if closure_params => closure_params = false,
+                // `||` is either a closure without parameters or a logical or,
+                // and contains no comma either way.
+                '|' if punct.spacing() == Spacing::Joint && is_punct(tokens.get(i + 1), '|') => {
+                    i += 2;
+                    continue;
+                }
+                '|' if i > start && !is_operand(&tokens[i - 1]) => closure_params = true,
+                _ => {}
+            },
+            TokenTree::Ident(ref ident) if depth == 0 && ident.to_string() == "if" => {
+                expression = true;
+            }
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+                // A match arm whose body is a block.
+                if arrow == Some(i) {
+                    return if is_punct(tokens.get(i + 1), ',') {
+                        i + 2
+                    } else {
+                        i + 1
+                    };
+                }
+                if !is_continued(tokens.get(i + 1)) {
+                    return i + 1;
+                }
+            }
+            TokenTree::Group(_) | TokenTree::Ident(_) | TokenTree::Literal(_) => {}
+        }
+        i += 1;
+    }
+
+    match first_comma {
+        Some(comma) if depth > 0 => comma + 1,
+        _ => i,
+    }
+}
+
+// Whether a block is followed by more of the same element.
+fn is_continued(next: Option<&TokenTree>) -> bool {
+    match next {
+        Some(TokenTree::Ident(ident)) => {
+            let ident = ident.to_string();
+            ident == "else" || ident == "as" || ident == "if"
+        }
+        Some(TokenTree::Punct(punct)) => match punct.as_char() {
+            ',' | ';' | '.' | '?' | '>' | '=' | '|' => true,
+            _ => false,
+        },
+        None | Some(TokenTree::Group(_)) | Some(TokenTree::Literal(_)) => false,
+    }
+}
+
+// `::<`
+fn is_turbofish(tokens: &[TokenTree], i: usize) -> bool {
+    i >= 2 && is_punct(tokens.get(i - 1), ':') && is_punct(tokens.get(i - 2), ':')
+}
+
+// Whether a `|` after this token is a binary operator or an or-pattern,
+// rather than the start of closure parameters.
+fn is_operand(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Ident(ident) => match ident.to_string().as_str() {
+            "move" | "async" | "return" | "break" | "in" | "yield" => false,
+            _ => true,
+        },
+        TokenTree::Punct(punct) => punct.as_char() == '?',
+        TokenTree::Group(_) | TokenTree::Literal(_) => true,
+    }
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    match token {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == ch,
+        _ => false,
+    }
+}
@@ -2,27 +2,58 @@ use crate::error::{Error, Result};
 use crate::iter::Iter;
 use crate::token;
 use proc_macro::Group;
+use std::fmt::{self, Display};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Release {
+    pub major: u16,
     pub minor: u16,
     pub patch: Option<u16>,
 }
 
+impl Display for Release {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(formatter, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
 pub fn parse(paren: Group, iter: Iter) -> Result<Release> {
     try_parse(iter).map_err(|()| Error::group(paren, "expected rustc release number, like 1.31"))
 }
 
+pub fn parse_str(string: &str) -> Option<Release> {
+    let mut digits = string.trim().split('.');
+    let major = digits.next()?.parse().ok()?;
+    let minor = digits.next()?.parse().ok()?;
+    let patch = match digits.next() {
+        Some(patch) => Some(patch.parse().ok()?),
+        None => None,
+    };
+    if major == 0 || digits.next().is_some() {
+        return None;
+    }
+    Some(Release {
+        major,
+        minor,
+        patch,
+    })
+}
+
 fn try_parse(iter: Iter) -> Result<Release, ()> {
     let major_minor = token::parse_literal(iter).map_err(drop)?;
     let string = major_minor.to_string();
 
-    if !string.starts_with("1.") {
+    let mut digits = string.splitn(2, '.');
+    let major: u16 = digits.next().ok_or(())?.parse().map_err(drop)?;
+    let minor: u16 = digits.next().ok_or(())?.parse().map_err(drop)?;
+    if major == 0 {
         return Err(());
     }
 
-    let minor: u16 = string[2..].parse().map_err(drop)?;
-
     let patch = if token::parse_optional_punct(iter, '.').is_some() {
         let int = token::parse_literal(iter).map_err(drop)?;
         Some(int.to_string().parse().map_err(drop)?)
@@ -30,5 +61,9 @@ fn try_parse(iter: Iter) -> Result<Release, ()> {
         None
     };
 
-    Ok(Release { minor, patch })
+    Ok(Release {
+        major,
+        minor,
+        patch,
+    })
 }
@@ -0,0 +1,52 @@
+use crate::error::{Error, Result};
+use crate::expr::{self, Expr};
+use crate::{iter, token};
+use proc_macro::{Delimiter, Span, TokenStream, TokenTree};
+
+pub struct Arm {
+    pub condition: Option<Expr>,
+    pub span: Span,
+    pub body: TokenStream,
+}
+
+pub fn parse(input: TokenStream) -> Result<Vec<Arm>> {
+    let ref mut input = iter::new(input);
+    let mut arms = Vec::new();
+
+    while input.peek().is_some() {
+        let span = input.peek().map_or_else(Span::call_site, TokenTree::span);
+        let condition = if token::parse_optional_keyword(input, "_").is_some() {
+            None
+        } else {
+            Some(expr::parse(input)?)
+        };
+
+        token::parse_punct(input, '=')?;
+        token::parse_punct(input, '>')?;
+
+        let body = match input.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                group.stream()
+            }
+            unexpected => {
+                let span = unexpected
+                    .as_ref()
+                    .map_or_else(Span::call_site, TokenTree::span);
+                return Err(Error::new(span, "expected `{`"));
+            }
+        };
+
+        arms.push(Arm {
+            condition,
+            span,
+            body,
+        });
+
+        if input.peek().is_none() {
+            break;
+        }
+        token::parse_punct(input, ',')?;
+    }
+
+    Ok(arms)
+}
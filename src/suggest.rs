@@ -0,0 +1,51 @@
+use std::cmp;
+
+// The candidate closest to a misspelled word, if any is close enough to be
+// what was meant.
+pub fn similar<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let mut best = None;
+    for &candidate in candidates {
+        let distance = distance(word, candidate);
+        let threshold = cmp::max(1, candidate.len() / 3);
+        let is_better = match best {
+            Some((best_distance, _)) => distance < best_distance,
+            None => true,
+        };
+        if distance <= threshold && is_better {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_distance, candidate)| candidate)
+}
+
+// Levenshtein distance in which swapping two adjacent characters, as in
+// `nighlty`, also counts as a single edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // d[i][j] is the distance between the first i chars of a and the first j
+    // chars of b.
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = cmp::min(
+                cmp::min(d[i - 1][j] + 1, d[i][j - 1] + 1),
+                d[i - 1][j - 1] + cost,
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = cmp::min(d[i][j], d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
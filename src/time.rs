@@ -12,6 +12,15 @@ const CYCLE: u64 = 365 * 4 + 1;
 
 const DAYS_BY_MONTH: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    if month == 2 && is_leap_year {
+        29
+    } else {
+        DAYS_BY_MONTH[month as usize - 1]
+    }
+}
+
 pub fn today() -> Date {
     let default = Date {
         year: 2025,
@@ -39,7 +48,7 @@ fn try_today() -> Option<Date> {
     let mut year = BASE_YEAR + cycle as u16 * 4;
     let mut month = BASE_MONTH;
     loop {
-        let days_in_month = DAYS_BY_MONTH[month as usize - 1];
+        let days_in_month = days_in_month(year, month);
         if rem < days_in_month as u64 {
             let day = rem as u8 + 1;
             return Some(Date { year, month, day });
@@ -1,18 +1,65 @@
 #![allow(dead_code)]
 
 use crate::date::Date;
+use crate::release::Release;
+use std::fmt::{self, Display};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Version {
+    pub major: u16,
     pub minor: u16,
     pub patch: u16,
     pub channel: Channel,
+    pub commit_hash: Option<&'static str>,
+    pub commit_date: Option<Date>,
+    pub host: Option<&'static str>,
+    pub llvm: Option<&'static str>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Channel {
     Stable,
-    Beta,
+    Beta(Option<u16>),
     Nightly(Date),
     Dev,
 }
+
+impl Version {
+    // The stable compiler of the given release, with nothing else known.
+    pub fn from_release(release: Release) -> Self {
+        Version {
+            major: release.major,
+            minor: release.minor,
+            patch: release.patch.unwrap_or(0),
+            channel: Channel::Stable,
+            commit_hash: None,
+            commit_date: None,
+            host: None,
+            llvm: None,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        match self.channel {
+            Channel::Stable => Ok(()),
+            Channel::Beta(None) => formatter.write_str("-beta"),
+            Channel::Beta(Some(number)) => write!(formatter, "-beta.{}", number),
+            Channel::Nightly(_) => formatter.write_str("-nightly"),
+            Channel::Dev => formatter.write_str("-dev"),
+        }
+    }
+}
+
+impl Channel {
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Beta(_) => "beta",
+            Channel::Nightly(_) => "nightly",
+            Channel::Dev => "dev",
+        }
+    }
+}
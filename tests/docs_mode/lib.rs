@@ -0,0 +1,28 @@
+// Documented by tests/test_docs_mode.rs in documentation mode.
+
+/// Kept on every compiler.
+#[rustversion::since(1.31)]
+pub fn kept() {}
+
+/// Removed on every compiler, but documented.
+#[rustversion::before(1.31)]
+pub fn removed() {}
+
+pub struct S;
+
+impl S {
+    /// Removed on every compiler, but documented.
+    #[rustversion::before(1.31)]
+    pub fn removed_method(&self) {}
+}
+
+rustversion::select! {
+    since(1.31) => {
+        /// Selected.
+        pub fn alternative() -> u8 { 1 }
+    },
+    _ => {
+        /// Not selected.
+        pub fn alternative() -> u8 { 0 }
+    },
+}
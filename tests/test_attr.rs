@@ -0,0 +1,86 @@
+#![allow(clippy::used_underscore_items)]
+
+use std::mem;
+
+#[rustversion::attr(all(), repr(u8), else = repr(u16))]
+enum _Then {
+    _A,
+}
+
+#[rustversion::attr(any(), repr(u8), else = repr(u16))]
+enum _Else {
+    _A,
+}
+
+#[rustversion::attr(cfg(test), repr(u8), else = repr(u16))]
+enum _CfgThen {
+    _A,
+}
+
+#[rustversion::attr(cfg(not(test)), repr(u8), else = repr(u16))]
+enum _CfgElse {
+    _A,
+}
+
+#[rustversion::attr(any(), derive(Debug), else = const)]
+fn _else_const() {}
+const _ELSE_CONST: () = _else_const();
+
+#[rustversion::attr(cfg(not(test)), derive(Debug), else = const)]
+fn _cfg_else_const() {}
+const _CFG_ELSE_CONST: () = _cfg_else_const();
+
+#[rustversion::attr(all(), const, else = must_use)]
+fn _then_const() {}
+const _THEN_CONST: () = _then_const();
+
+#[rustversion::attr(any(), must_use, else = derive(Clone, Copy), derive(Debug))]
+struct _Attrs;
+
+#[test]
+fn test_else() {
+    assert_eq!(mem::size_of::<_Then>(), 1);
+    assert_eq!(mem::size_of::<_Else>(), 2);
+    assert_eq!(mem::size_of::<_CfgThen>(), 1);
+    assert_eq!(mem::size_of::<_CfgElse>(), 2);
+
+    let attrs = _Attrs;
+    let copy = attrs;
+    assert_eq!(format!("{:?} {:?}", attrs, copy), "_Attrs _Attrs");
+}
+
+#[rustversion::attr_match(any() => repr(u8), all() => repr(u16), _ => repr(u32))]
+enum _Second {
+    _A,
+}
+
+#[rustversion::attr_match(any() => repr(u8), any() => repr(u16), _ => repr(u32))]
+enum _Fallback {
+    _A,
+}
+
+#[rustversion::attr_match(any() => repr(u8), any() => repr(u16))]
+enum _Unmatched {
+    _A,
+}
+
+#[rustversion::attr_match(
+    since(1.31) => derive(Clone, Copy), derive(Debug),
+    _ => derive(Debug),
+)]
+struct _Match;
+
+#[rustversion::attr_match(before(1.31) => must_use, since(1.31) => const)]
+fn _match_const() {}
+const _MATCH_CONST: () = _match_const();
+
+#[test]
+fn test_match() {
+    assert_eq!(mem::size_of::<_Second>(), 2);
+    assert_eq!(mem::size_of::<_Fallback>(), 4);
+    assert_eq!(mem::size_of::<_Unmatched>(), 0);
+
+    let arms = _Match;
+    let copy = arms;
+    assert_eq!(format!("{:?} {:?}", arms, copy), "_Match _Match");
+}
@@ -0,0 +1,52 @@
+#![allow(clippy::assertions_on_constants)]
+
+#[rustversion::all(since(1.31), cfg(test))]
+fn with_test() -> bool {
+    true
+}
+
+#[rustversion::all(since(1.31), cfg(not(test)))]
+fn with_test() -> bool {
+    false
+}
+
+#[rustversion::any(before(1.31), cfg(any()))]
+fn never() {}
+
+#[rustversion::attr(cfg(test), const)]
+fn maybe_const() -> u8 {
+    1
+}
+const MAYBE_CONST: u8 = maybe_const();
+
+#[rustversion::attr(all(since(1.31), cfg(test)), derive(Debug))]
+struct Derived;
+
+#[test]
+fn test_item() {
+    assert!(with_test());
+    assert_eq!(format!("{:?}", Derived), "Derived");
+    assert_eq!(MAYBE_CONST, 1);
+}
+
+#[test]
+fn test_expr() {
+    assert!(rustversion::cfg!(all(since(1.31), cfg(test))));
+    assert!(!rustversion::cfg!(all(since(1.31), cfg(not(test)))));
+    assert!(rustversion::cfg!(any(before(1.31), cfg(all()))));
+}
+
+mod imported {
+    use rustversion::cfg;
+
+    #[rustversion::all(since(1.31), cfg(test))]
+    fn with_test() -> bool {
+        true
+    }
+
+    #[test]
+    fn test_expr() {
+        assert!(with_test());
+        assert!(cfg!(all(since(1.31), cfg(test))));
+    }
+}
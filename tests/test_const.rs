@@ -40,3 +40,55 @@ const _DOC_BELOW: () = _doc_below();
 #[rustversion::attr(all(), const)]
 fn _doc_above() {}
 const _DOC_ABOVE: () = _doc_above();
+
+struct _Impl;
+
+macro_rules! method {
+    () => {
+        fn _from_macro() {}
+    };
+}
+
+#[rustversion::attr(all(), const)]
+impl _Impl {
+    const _ASSOC: fn() = Self::_method;
+
+    method!();
+
+    fn _method() {}
+
+    pub(crate) unsafe fn _unsafe_method() {}
+
+    pub const fn _already_const() {}
+
+    #[rustversion::not_const]
+    fn _not_const() {
+        let _ = Vec::<u8>::new().clone();
+    }
+}
+
+const _METHOD: () = _Impl::_method();
+const _UNSAFE_METHOD: () = unsafe { _Impl::_unsafe_method() };
+
+struct _Generic<T>(T);
+
+#[rustversion::attr(all(), const)]
+impl<T> _Generic<T>
+where
+    for<'a> &'a T: Copy,
+{
+    fn _method() {}
+}
+
+const _GENERIC: () = _Generic::<u8>::_method();
+
+struct _InnerAttr;
+
+#[rustversion::attr(all(), const)]
+impl _InnerAttr {
+    #![allow(clippy::unused_self)]
+
+    fn _method() {}
+}
+
+const _INNER_ATTR: () = _InnerAttr::_method();
@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_docs_mode() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let fixture = manifest_dir.join("tests").join("docs_mode");
+    let target_dir = manifest_dir.join("target").join("tests").join("docs_mode");
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    let status = Command::new(cargo)
+        .arg("doc")
+        .arg("--no-deps")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .env("RUSTVERSION_DOCS", "1")
+        .env_remove("RUSTDOCFLAGS")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let doc = target_dir.join("doc").join("rustversion_docs_mode");
+    let read = |file: &str| fs::read_to_string(doc.join(file)).unwrap();
+
+    let kept = read("fn.kept.html");
+    assert!(kept.contains("Available on <strong>Rust 1.31+</strong> only."));
+
+    let removed = read("fn.removed.html");
+    assert!(removed.contains("Removed on every compiler, but documented."));
+    assert!(removed.contains("Available on <strong>rustc <code>before(1.31)</code></strong> only."));
+
+    let method = read("struct.S.html");
+    assert!(method.contains("removed_method"));
+
+    let alternative = read("fn.alternative.html");
+    assert!(alternative.contains("Selected."));
+    assert!(!alternative.contains("Not selected."));
+}
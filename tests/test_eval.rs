@@ -1,14 +1,33 @@
+// Some of these conditions are always true given the rust-version in
+// Cargo.toml, which rustversion warns about.
+#![allow(deprecated)]
+
 #[rustversion::any(
     stable,
     stable(1.34),
     stable(1.34.0),
     beta,
+    beta(1.34),
+    beta(1.34.0),
+    beta(1.34, 3),
     nightly,
     nightly(2020-02-25),
+    nightly(2020-02-29),
+    nightly(1.34),
+    nightly(1.34.0),
     since(1.34),
     since(2020-02-25),
     before(1.34),
     before(2020-02-25),
+    between(1.34, 1.40),
+    between(1.34, =1.40),
+    between(1.34, =1.34),
+    between(2020-01-01, 2020-02-25),
+    since(msrv),
+    between(msrv, 1.80),
+    since(2.0),
+    before(2.0.1),
+    stable(2.0),
     not(nightly),
     all(stable, beta, nightly),
 )]
@@ -18,3 +37,28 @@ fn success() {}
 fn test() {
     success();
 }
+
+#[test]
+fn test_commit_date() {
+    // Distro builds and RUSTVERSION_OVERRIDE may leave the commit date
+    // unknown, which puts a stable or beta compiler before every date.
+    if rustversion::cfg!(all(not(nightly), before(2015 - 05 - 15))) {
+        return;
+    }
+    // Every compiler that runs this test was built after Rust 1.31.
+    assert!(rustversion::cfg!(since(2018 - 12 - 01)));
+    assert!(!rustversion::cfg!(before(2018 - 12 - 01)));
+}
+
+#[test]
+fn test_between() {
+    assert!(rustversion::cfg!(between(1.31, 3.0)));
+    assert!(!rustversion::cfg!(between(1.0, 1.31)));
+    assert!(rustversion::cfg!(between(1.0, =1.31)) == rustversion::cfg!(stable(1.31)));
+}
+
+#[test]
+fn test_msrv() {
+    assert!(rustversion::cfg!(since(msrv)));
+    assert!(!rustversion::cfg!(before(msrv)));
+}
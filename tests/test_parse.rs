@@ -12,38 +12,71 @@ fn test_parse() {
         (
             "rustc 1.0.0 (a59de37e9 2015-05-13) (built 2015-05-14)",
             Version {
+                major: 1,
                 minor: 0,
                 patch: 0,
                 channel: Stable,
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2015,
+                    month: 5,
+                    day: 13,
+                }),
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.18.0",
             Version {
+                major: 1,
                 minor: 18,
                 patch: 0,
                 channel: Stable,
+                commit_hash: None,
+                commit_date: None,
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.24.1 (d3ae9a9e0 2018-02-27)",
             Version {
+                major: 1,
                 minor: 24,
                 patch: 1,
                 channel: Stable,
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2018,
+                    month: 2,
+                    day: 27,
+                }),
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.35.0-beta.3 (c13114dc8 2019-04-27)",
             Version {
+                major: 1,
                 minor: 35,
                 patch: 0,
-                channel: Beta,
+                channel: Beta(Some(3)),
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2019,
+                    month: 4,
+                    day: 27,
+                }),
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.36.0-nightly (938d4ffe1 2019-04-27)",
             Version {
+                major: 1,
                 minor: 36,
                 patch: 0,
                 channel: Nightly(Date {
@@ -51,28 +84,47 @@ fn test_parse() {
                     month: 4,
                     day: 27,
                 }),
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2019,
+                    month: 4,
+                    day: 27,
+                }),
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.36.0-dev",
             Version {
+                major: 1,
                 minor: 36,
                 patch: 0,
                 channel: Dev,
+                commit_hash: None,
+                commit_date: None,
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.36.0-nightly",
             Version {
+                major: 1,
                 minor: 36,
                 patch: 0,
                 channel: Dev,
+                commit_hash: None,
+                commit_date: None,
+                host: None,
+                llvm: None,
             },
         ),
         (
             "warning: invalid logging spec 'warning', ignoring it
              rustc 1.30.0-nightly (3bc2ca7e4 2018-09-20)",
             Version {
+                major: 1,
                 minor: 30,
                 patch: 0,
                 channel: Nightly(Date {
@@ -80,14 +132,89 @@ fn test_parse() {
                     month: 9,
                     day: 20,
                 }),
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2018,
+                    month: 9,
+                    day: 20,
+                }),
+                host: None,
+                llvm: None,
             },
         ),
         (
             "rustc 1.52.1-nightly (gentoo)",
             Version {
+                major: 1,
                 minor: 52,
                 patch: 1,
                 channel: Dev,
+                commit_hash: None,
+                commit_date: None,
+                host: None,
+                llvm: None,
+            },
+        ),
+        (
+            "rustc 2.3.1-beta.2 (9fb8e6c0a 2030-01-15)",
+            Version {
+                major: 2,
+                minor: 3,
+                patch: 1,
+                channel: Beta(Some(2)),
+                commit_hash: None,
+                commit_date: Some(Date {
+                    year: 2030,
+                    month: 1,
+                    day: 15,
+                }),
+                host: None,
+                llvm: None,
+            },
+        ),
+        (
+            "rustc 1.87.0-nightly (85abb2763 2025-03-01)
+             binary: rustc
+             commit-hash: 85abb276361ed2a4ee72f7d3f9d6ec3e62d7e16b
+             commit-date: 2025-03-01
+             host: x86_64-unknown-linux-gnu
+             release: 1.87.0-nightly
+             LLVM version: 20.1.0",
+            Version {
+                major: 1,
+                minor: 87,
+                patch: 0,
+                channel: Nightly(Date {
+                    year: 2025,
+                    month: 3,
+                    day: 1,
+                }),
+                commit_hash: Some("85abb276361ed2a4ee72f7d3f9d6ec3e62d7e16b".to_owned()),
+                commit_date: Some(Date {
+                    year: 2025,
+                    month: 3,
+                    day: 1,
+                }),
+                host: Some("x86_64-unknown-linux-gnu".to_owned()),
+                llvm: Some("20.1.0".to_owned()),
+            },
+        ),
+        (
+            "rustc 1.36.0-dev
+             binary: rustc
+             commit-hash: unknown
+             commit-date: unknown
+             host: aarch64-apple-darwin
+             release: 1.36.0-dev",
+            Version {
+                major: 1,
+                minor: 36,
+                patch: 0,
+                channel: Dev,
+                commit_hash: None,
+                commit_date: None,
+                host: Some("aarch64-apple-darwin".to_owned()),
+                llvm: None,
             },
         ),
     ];
@@ -0,0 +1,153 @@
+#![allow(clippy::used_underscore_items)]
+
+use std::collections::HashMap;
+
+#[rustversion::process]
+#[derive(Debug, Default)]
+struct Fields {
+    kept: HashMap<u8, Vec<u8>>,
+    #[rustversion(any())]
+    removed: u8,
+    #[rustversion(all())]
+    #[rustversion(since(1.31))]
+    chained: u8,
+    #[rustversion(attr(all(), allow(dead_code)))]
+    unused: u8,
+}
+
+#[rustversion::process]
+#[derive(Debug)]
+enum Variants {
+    Kept,
+    #[rustversion(any())]
+    Removed {
+        a: u8,
+        b: u8,
+    },
+    #[rustversion(all())]
+    Struct {
+        a: u8,
+        b: u8,
+    },
+    #[rustversion(cfg(test))]
+    Cfg(u8),
+    #[rustversion(cfg(not(test)))]
+    NotCfg,
+}
+
+#[rustversion::process]
+#[derive(Clone, Copy)]
+enum Flags {
+    #[rustversion(any())]
+    A = 1 << 0,
+    B = 1 << 1,
+    #[rustversion(all())]
+    C = 1 << 2,
+    #[rustversion(any())]
+    D = 1 << 3,
+    #[rustversion(any())]
+    Tuple(HashMap<u8, u8>, u8) = 1 << 4,
+}
+
+#[rustversion::process]
+struct Tuple(#[rustversion(any())] HashMap<u8, u8>, u8);
+
+fn flags(flags: Flags) -> u8 {
+    // Exhaustive only if every removed variant is gone.
+    match flags {
+        Flags::B | Flags::C => flags as u8,
+    }
+}
+
+#[rustversion::process]
+fn arms(variant: &Variants) -> u8 {
+    match variant {
+        Variants::Kept => 0,
+        #[rustversion(all())]
+        Variants::Struct { a, b } if a < b => {
+            assert!(a < b);
+            a + 1
+        }
+        #[rustversion(any())]
+        Variants::Removed { .. } => 2,
+        #[rustversion(any())]
+        Variants::Removed { .. } => pick::<u8, u8>(2, 2),
+        #[rustversion(any())]
+        Variants::Removed { .. } => |a: u8, b: u8| a + b,
+        #[rustversion(all())]
+        Variants::Struct { .. } => pick::<u8, u8>(3, 0),
+        #[rustversion(all())]
+        Variants::Cfg(a) => *a,
+    }
+}
+
+fn pick<A, B>(a: A, _b: B) -> A {
+    a
+}
+
+#[rustversion::process]
+fn statements() -> Vec<u8> {
+    let mut vec = Vec::new();
+    #[rustversion(any())]
+    vec.push(0);
+    #[rustversion(all())]
+    vec.push(1);
+    #[rustversion(any())]
+    if vec.is_empty() {
+        vec.push(2);
+    } else {
+        vec.push(3);
+    }
+    #[rustversion(all())]
+    for i in 4..6 {
+        vec.push(i);
+    }
+    #[rustversion(any())]
+    let add = |a: u8, b: u8| a * b;
+    #[rustversion(all())]
+    let add = |a: u8, b: u8| a + b;
+    vec.push(add(3, 3));
+    #[rustversion(any())]
+    fn removed<A, B>()
+    where
+        A: Copy,
+        B: Copy,
+    {
+    }
+    vec
+}
+
+struct Methods;
+
+#[rustversion::process]
+impl Methods {
+    #[rustversion(attr(all(), const))]
+    fn method() -> u8 {
+        1
+    }
+}
+
+const METHOD: u8 = Methods::method();
+
+#[test]
+fn test_process() {
+    let fields = Fields::default();
+    assert_eq!(fields.kept.len(), 0);
+    assert_eq!(fields.chained, 0);
+    assert_eq!(
+        format!("{:?}", fields),
+        "Fields { kept: {}, chained: 0, unused: 0 }",
+    );
+
+    assert_eq!(arms(&Variants::Kept), 0);
+    assert_eq!(arms(&Variants::Struct { a: 1, b: 2 }), 2);
+    assert_eq!(arms(&Variants::Struct { a: 2, b: 1 }), 3);
+    assert_eq!(arms(&Variants::Cfg(4)), 4);
+
+    assert_eq!(flags(Flags::B), 2);
+    assert_eq!(flags(Flags::C), 4);
+    assert_eq!(Tuple(1).0, 1);
+
+    assert_eq!(statements(), [1, 4, 5, 6]);
+    assert_eq!(METHOD, 1);
+}
@@ -0,0 +1,102 @@
+#![allow(clippy::used_underscore_items)]
+
+#[rustversion::attr(since(1.82), unsafe)]
+extern "C" {
+    fn abs(i: i32) -> i32;
+}
+
+#[rustversion::attr(since(1.82), unsafe)]
+#[allow(dead_code)]
+extern "C" {
+    #[link_name = "abs"]
+    fn _abs_doc(i: i32) -> i32;
+}
+
+#[rustversion::attr(since(1.82), unsafe(no_mangle))]
+pub extern "C" fn rustversion_test_unsafe_attr() -> i32 {
+    1
+}
+
+#[rustversion::attr(any(), unsafe(no_mangle))]
+pub extern "C" fn rustversion_test_unsafe_attr_fallback() -> i32 {
+    1
+}
+
+#[rustversion::attr(cfg(test), unsafe(export_name = "rustversion_test_unsafe_attr_cfg"))]
+pub extern "C" fn _unsafe_attr_cfg() -> i32 {
+    1
+}
+
+#[test]
+fn test_unsafe() {
+    assert_eq!(unsafe { abs(-1) }, 1);
+    assert_eq!(rustversion_test_unsafe_attr(), 1);
+    assert_eq!(rustversion_test_unsafe_attr_fallback(), 1);
+    assert_eq!(_unsafe_attr_cfg(), 1);
+}
+
+#[rustversion::attr(all(), unsafe)]
+fn _unsafe_fn() {}
+
+#[rustversion::attr(all(), unsafe)]
+pub(crate) const extern "C" fn _unsafe_between() {}
+
+#[rustversion::attr(all(), async)]
+pub fn _async_fn() {}
+
+#[rustversion::attr(all(), async)]
+unsafe fn _async_before_unsafe() {}
+
+#[rustversion::attr(all(), extern "C")]
+unsafe fn _extern_fn(_: extern "C" fn()) {}
+
+#[rustversion::attr(any(), unsafe, else = async)]
+fn _else_async() {}
+
+#[test]
+fn test_qualifier() {
+    unsafe { _unsafe_fn() };
+    const _UNSAFE_BETWEEN: () = unsafe { _unsafe_between() };
+
+    let _: fn() -> _ = _async_fn;
+    let _ = || async { _async_fn().await };
+    let _ = || async { unsafe { _async_before_unsafe() }.await };
+    let _ = || async { _else_async().await };
+
+    let _: unsafe extern "C" fn(extern "C" fn()) = _extern_fn;
+}
+
+#[rustversion::attr(all(), !const)]
+pub const fn _not_const() -> Vec<u8> {
+    Vec::new().clone()
+}
+
+#[rustversion::attr(all(), !unsafe)]
+const unsafe extern "C" fn _not_unsafe() {}
+
+#[rustversion::attr(all(), !async)]
+async unsafe fn _not_async() {}
+
+#[rustversion::attr(all(), !extern)]
+unsafe extern "C" fn _not_extern() {}
+
+#[rustversion::attr(all(), !unsafe)]
+unsafe extern "C" {
+    #[link_name = "abs"]
+    fn _abs_not_unsafe(i: i32) -> i32;
+}
+
+#[rustversion::attr(any(), const, else = !const)]
+const fn _else_not_const() -> Vec<u8> {
+    Vec::new().clone()
+}
+
+#[test]
+fn test_not_qualifier() {
+    assert!(_not_const().is_empty());
+    const _NOT_UNSAFE: extern "C" fn() = _not_unsafe;
+    let _: unsafe fn() = _not_async;
+    let _: unsafe fn() = _not_extern;
+    assert_eq!(unsafe { _abs_not_unsafe(-1) }, 1);
+    assert!(_else_not_const().is_empty());
+}
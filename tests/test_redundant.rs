@@ -0,0 +1,92 @@
+// The rust-version in Cargo.toml is 1.31, so every condition below is the same
+// on all compilers it allows. The warnings must not change where the items end
+// up, including inside impl blocks where nothing else may be inserted.
+#![allow(deprecated, dead_code, unknown_lints)]
+#![deny(unfulfilled_lint_expectations)]
+
+struct S;
+
+impl S {
+    #[rustversion::since(1.31)]
+    fn kept() -> bool {
+        true
+    }
+
+    #[rustversion::before(1.31)]
+    fn removed() {}
+
+    #[rustversion::since(1.31)]
+    const KEPT: bool = true;
+}
+
+impl Clone for S {
+    #[rustversion::since(1.31)]
+    fn clone(&self) -> Self {
+        S
+    }
+
+    #[rustversion::before(1.31)]
+    fn clone_from(&mut self, _source: &Self) {}
+}
+
+trait Trait {
+    #[rustversion::since(1.31)]
+    fn required(&self);
+}
+
+extern "C" {
+    #[rustversion::since(1.31)]
+    fn abs(i: i32) -> i32;
+}
+
+#[rustversion::attr(since(1.31), derive(Debug))]
+struct Derived;
+
+#[rustversion::since(1.31)]
+macro_rules! kept {
+    () => {
+        true
+    };
+}
+
+impl S {
+    #[rustversion::since(1.31)]
+    fn inner_attr() -> bool {
+        #![allow(unused_variables)]
+        let unused = 0;
+        true
+    }
+}
+
+// Each module expects the warning from the item inside it.
+#[rustversion::attr(since(1.81), expect(deprecated), else = allow(deprecated))]
+mod warned_beside {
+    #[rustversion::since(1.31)]
+    pub struct Kept;
+}
+
+#[rustversion::attr(since(1.81), expect(deprecated), else = allow(deprecated))]
+mod warned_in_body {
+    pub struct S;
+
+    impl S {
+        #[rustversion::since(1.31)]
+        pub fn kept() -> bool {
+            #![allow(unused_variables)]
+            let unused = 0;
+            true
+        }
+    }
+}
+
+#[test]
+fn test() {
+    assert!(S::kept());
+    assert!(S::KEPT);
+    let _ = S.clone();
+    let _ = format!("{:?}", Derived);
+    assert!(kept!());
+    assert!(S::inner_attr());
+    let _ = warned_beside::Kept;
+    assert!(warned_in_body::S::kept());
+}
@@ -0,0 +1,17 @@
+#[rustversion::remove_after(999.0, "kept until then")]
+fn polyfill() -> bool {
+    true
+}
+
+// The rust-version in Cargo.toml is 1.31, so this is kept on every compiler,
+// however new.
+#[rustversion::remove_after(1.32, "checked against rust-version, not the compiler")]
+fn msrv_polyfill() -> bool {
+    true
+}
+
+#[test]
+fn test_kept() {
+    assert!(polyfill());
+    assert!(msrv_polyfill());
+}
@@ -0,0 +1,47 @@
+rustversion::select! {
+    since(1.31) => {
+        fn selected() -> &'static str {
+            "since"
+        }
+    },
+    _ => {
+        fn selected() -> &'static str {
+            "fallback"
+        }
+    },
+}
+
+#[test]
+fn test_item() {
+    assert_eq!(selected(), "since");
+}
+
+#[test]
+fn test_expr() {
+    let value = rustversion::select! {
+        before(1.31) => { "before" },
+        all(since(1.31), before(1.31)) => { "never" },
+        _ => { "fallback" }
+    };
+    assert_eq!(value, "fallback");
+}
+
+#[test]
+fn test_expr_statements() {
+    let value = rustversion::select! {
+        before(1.31) => {{
+            let a = 0;
+            a
+        }},
+        _ => {{
+            let a = 1;
+            a + 1
+        }},
+    };
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn test_type() {
+    let _: rustversion::select!(since(1.31) => { u8 }, _ => { u16 }) = 0u8;
+}
@@ -0,0 +1,43 @@
+#[test]
+fn test_version() {
+    let version = rustversion::version!();
+    let number = format!(
+        "{}.{}.{}",
+        rustversion::major!(),
+        rustversion::minor!(),
+        rustversion::patch!(),
+    );
+    assert!(version.starts_with(&number), "{} {}", version, number);
+
+    let channel = rustversion::channel!();
+    if channel == "stable" {
+        assert_eq!(version, number);
+    } else {
+        // A beta may be followed by its number, as in 1.85.0-beta.3.
+        let suffix = &version[number.len()..];
+        assert!(
+            suffix.starts_with(&format!("-{}", channel)),
+            "{} {}",
+            version,
+            channel,
+        );
+    }
+}
+
+#[test]
+fn test_channel() {
+    let channel = rustversion::channel!();
+    assert_eq!(channel == "stable", rustversion::cfg!(stable));
+    assert_eq!(channel == "beta", rustversion::cfg!(beta));
+    assert_eq!(
+        channel == "nightly" || channel == "dev",
+        rustversion::cfg!(nightly),
+    );
+}
+
+#[test]
+fn test_integer() {
+    let major: u32 = rustversion::major!();
+    let minor: u32 = rustversion::minor!();
+    assert!((major, minor) >= (1, 31));
+}
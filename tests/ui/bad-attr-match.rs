@@ -0,0 +1,10 @@
+#[rustversion::attr_match(stable repr(u8))]
+enum A {}
+
+#[rustversion::attr_match(stable => repr(u8), nightly =>)]
+enum B {}
+
+#[rustversion::attr_match(cfg(test) => repr(u8))]
+enum C {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[rustversion::beta(1.85, three)]
+struct S;
+
+#[rustversion::any(beta(1.85, 3.0))]
+struct S;
+
+fn main() {}
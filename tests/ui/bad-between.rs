@@ -0,0 +1,13 @@
+#[rustversion::between(1.80, 1.70)]
+struct S;
+
+#[rustversion::between(1.80, 1.80)]
+struct S;
+
+#[rustversion::between(1.80, 2025-01-01)]
+struct S;
+
+#[rustversion::between(1.80)]
+struct S;
+
+fn main() {}
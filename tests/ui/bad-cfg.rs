@@ -0,0 +1,8 @@
+#[rustversion::all(stable, cfg())]
+struct S;
+
+rustversion::select! {
+    cfg(test) => { struct T; }
+}
+
+fn main() {}
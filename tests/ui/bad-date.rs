@@ -4,4 +4,19 @@ struct S;
 #[rustversion::any(nightly(stable))]
 struct S;
 
+#[rustversion::nightly(2025-02-30)]
+struct S;
+
+#[rustversion::since(2023-04-31)]
+struct S;
+
+#[rustversion::before(2025-13-01)]
+struct S;
+
+#[rustversion::nightly(2025-00-10)]
+struct S;
+
+#[rustversion::since(2014-12-31)]
+struct S;
+
 fn main() {}
@@ -0,0 +1,13 @@
+#[rustversion::attr(stable, repr(u8) else = repr(u16))]
+enum A {}
+
+#[rustversion::attr(stable, repr(u8), else repr(u16))]
+enum B {}
+
+#[rustversion::attr(stable, repr(u8), else =)]
+enum C {}
+
+#[rustversion::attr(stable, const, else = const, must_use)]
+fn d() {}
+
+fn main() {}
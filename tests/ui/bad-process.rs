@@ -0,0 +1,16 @@
+#[rustversion::process(x)]
+struct A;
+
+#[rustversion::process]
+struct B {
+    #[rustversion(bogus)]
+    b: u8,
+}
+
+#[rustversion::process]
+struct C {
+    #[rustversion(attr)]
+    c: u8,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#[rustversion::attr(all(), unsafe)]
+pub struct S;
+
+#[rustversion::attr(all(), unsafe)]
+extern crate core;
+
+#[rustversion::attr(all(), unsafe())]
+pub struct T;
+
+#[rustversion::attr(all(), unsafe)]
+pub unsafe fn f() {}
+
+#[rustversion::attr(all(), async)]
+extern "C" {}
+
+#[rustversion::attr(all(), !const)]
+pub fn g() {}
+
+#[rustversion::attr(all(), !unsafe)]
+extern "C" {}
+
+#[rustversion::attr(all(), !must_use)]
+pub fn h() {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+rustversion::select! {
+    nightly => struct S;
+}
+
+rustversion::select! {
+    stable
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+struct S;
+
+#[rustversion::attr(all(), const)]
+impl Default for S {
+    fn default() -> Self {
+        S
+    }
+}
+
+#[rustversion::attr(all(), const)]
+impl S {
+    async fn f() {}
+}
+
+fn main() {}
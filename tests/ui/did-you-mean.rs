@@ -0,0 +1,31 @@
+#[rustversion::any(nighlty)]
+struct A;
+
+#[rustversion::any(sinse(1.80))]
+struct B;
+
+#[rustversion::not(befor(1.80))]
+struct C;
+
+#[rustversion::since(1.80.0-beta)]
+struct D;
+
+#[rustversion::any(>= 1.80)]
+struct E;
+
+#[rustversion::since(< 1.80)]
+struct F;
+
+#[rustversion::any(version = "1.80")]
+struct G;
+
+#[rustversion::any(rust_version)]
+struct H;
+
+#[rustversion::since(mrsv)]
+struct I;
+
+#[rustversion::any(bogus)]
+struct J;
+
+fn main() {}
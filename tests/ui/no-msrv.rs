@@ -0,0 +1,4 @@
+#[rustversion::since(msrv)]
+struct S;
+
+fn main() {}
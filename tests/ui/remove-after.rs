@@ -0,0 +1,26 @@
+#[rustversion::remove_after(compiler(1.31), "use core::pin::Pin instead")]
+pub struct Pin;
+
+#[rustversion::remove_after(compiler(2020-01-01))]
+pub struct Date;
+
+#[rustversion::remove_after(compiler(999.0), "not yet")]
+pub struct Future;
+
+#[rustversion::remove_after(1.90)]
+pub struct NoRustVersion;
+
+#[rustversion::remove_after(2020-01-01)]
+pub struct NightlyWithoutCompiler;
+
+#[rustversion::remove_after(compiler(1.31), r#"raw "message""#)]
+pub struct Raw;
+
+#[rustversion::remove_after(compiler(1.31), "escaped\t\x41\u{2764}\0 and \
+    continued")]
+pub struct Escaped;
+
+#[rustversion::remove_after(1.31, 31)]
+pub struct BadMessage;
+
+fn main() {}